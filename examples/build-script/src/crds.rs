@@ -1,3 +0,0 @@
-pub mod podmonitor;
-pub mod scrapeconfig;
-pub mod servicemonitor;
@@ -0,0 +1,9 @@
+// WARNING: generated by kopium - manual changes will be overwritten
+// kopium version: 0.24.1
+
+/// Types for `podmonitors.monitoring.coreos.com`
+pub mod podmonitor;
+/// Types for `scrapeconfigs.monitoring.coreos.com`
+pub mod scrapeconfig;
+/// Types for `servicemonitors.monitoring.coreos.com`
+pub mod servicemonitor;
@@ -1,14 +1,15 @@
 // WARNING: generated by kopium - manual changes will be overwritten
-// kopium version: 0.23.0
+// kopium version: 0.24.1
 
 #[allow(unused_imports)]
 mod prelude {
-    pub use k8s_openapi::apimachinery::pkg::{apis::meta::v1::Condition, util::intstr::IntOrString};
     pub use kube::CustomResource;
+    pub use typed_builder::TypedBuilder;
     pub use schemars::JsonSchema;
-    pub use serde::{Deserialize, Serialize};
+    pub use serde::{Serialize, Deserialize};
     pub use std::collections::BTreeMap;
-    pub use typed_builder::TypedBuilder;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+    pub use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
 }
 
 use self::prelude::*;
@@ -98,7 +99,11 @@ pub struct PodMonitorSpec {
     )]
     #[builder(default, setter(strip_option))]
     pub pod_metrics_endpoints: Option<Vec<PodMonitorPodMetricsEndpoints>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "podTargetLabels")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "podTargetLabels"
+    )]
     #[builder(default, setter(strip_option))]
     pub pod_target_labels: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "sampleLimit")]
@@ -114,9 +119,13 @@ pub struct PodMonitorSpec {
     )]
     #[builder(default, setter(strip_option))]
     pub scrape_classic_histograms: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "scrapeProtocols")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "scrapeProtocols"
+    )]
     #[builder(default, setter(strip_option))]
-    pub scrape_protocols: Option<Vec<String>>,
+    pub scrape_protocols: Option<Vec<PodMonitorScrapeProtocols>>,
     pub selector: PodMonitorSelector,
     #[serde(
         default,
@@ -181,13 +190,21 @@ pub struct PodMonitorPodMetricsEndpoints {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "filterRunning")]
     #[builder(default, setter(strip_option))]
     pub filter_running: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "honorLabels")]
     #[builder(default, setter(strip_option))]
     pub honor_labels: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "honorTimestamps")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "honorTimestamps"
+    )]
     #[builder(default, setter(strip_option))]
     pub honor_timestamps: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -224,7 +241,9 @@ pub struct PodMonitorPodMetricsEndpoints {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<PodMonitorPodMetricsEndpointsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<PodMonitorPodMetricsEndpointsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -408,8 +427,9 @@ pub struct PodMonitorPodMetricsEndpointsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<PodMonitorPodMetricsEndpointsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<PodMonitorPodMetricsEndpointsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -826,9 +846,26 @@ pub enum PodMonitorPodMetricsEndpointsTlsConfigMinVersion {
     Tls13,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub enum PodMonitorScrapeProtocols {
+    PrometheusProto,
+    #[serde(rename = "OpenMetricsText0.0.1")]
+    OpenMetricsText001,
+    #[serde(rename = "OpenMetricsText1.0.0")]
+    OpenMetricsText100,
+    #[serde(rename = "PrometheusText0.0.4")]
+    PrometheusText004,
+    #[serde(rename = "PrometheusText1.0.0")]
+    PrometheusText100,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, TypedBuilder, JsonSchema)]
 pub struct PodMonitorSelector {
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "matchExpressions")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "matchExpressions"
+    )]
     #[builder(default, setter(strip_option))]
     pub match_expressions: Option<Vec<PodMonitorSelectorMatchExpressions>>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "matchLabels")]
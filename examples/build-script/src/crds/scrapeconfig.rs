@@ -1,14 +1,15 @@
 // WARNING: generated by kopium - manual changes will be overwritten
-// kopium version: 0.23.0
+// kopium version: 0.24.1
 
 #[allow(unused_imports)]
 mod prelude {
-    pub use k8s_openapi::apimachinery::pkg::{apis::meta::v1::Condition, util::intstr::IntOrString};
     pub use kube::CustomResource;
+    pub use typed_builder::TypedBuilder;
     pub use schemars::JsonSchema;
-    pub use serde::{Deserialize, Serialize};
+    pub use serde::{Serialize, Deserialize};
     pub use std::collections::BTreeMap;
-    pub use typed_builder::TypedBuilder;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+    pub use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
 }
 
 use self::prelude::*;
@@ -32,7 +33,11 @@ pub struct ScrapeConfigSpec {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "basicAuth")]
     #[builder(default, setter(strip_option))]
     pub basic_auth: Option<ScrapeConfigBasicAuth>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "consulSDConfigs")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "consulSDConfigs"
+    )]
     #[builder(default, setter(strip_option))]
     pub consul_sd_configs: Option<Vec<ScrapeConfigConsulSdConfigs>>,
     #[serde(
@@ -52,7 +57,11 @@ pub struct ScrapeConfigSpec {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "dnsSDConfigs")]
     #[builder(default, setter(strip_option))]
     pub dns_sd_configs: Option<Vec<ScrapeConfigDnsSdConfigs>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "dockerSDConfigs")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "dockerSDConfigs"
+    )]
     #[builder(default, setter(strip_option))]
     pub docker_sd_configs: Option<Vec<ScrapeConfigDockerSdConfigs>>,
     #[serde(
@@ -75,7 +84,11 @@ pub struct ScrapeConfigSpec {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "eurekaSDConfigs")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "eurekaSDConfigs"
+    )]
     #[builder(default, setter(strip_option))]
     pub eureka_sd_configs: Option<Vec<ScrapeConfigEurekaSdConfigs>>,
     #[serde(
@@ -91,13 +104,21 @@ pub struct ScrapeConfigSpec {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "gceSDConfigs")]
     #[builder(default, setter(strip_option))]
     pub gce_sd_configs: Option<Vec<ScrapeConfigGceSdConfigs>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "hetznerSDConfigs")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "hetznerSDConfigs"
+    )]
     #[builder(default, setter(strip_option))]
     pub hetzner_sd_configs: Option<Vec<ScrapeConfigHetznerSdConfigs>>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "honorLabels")]
     #[builder(default, setter(strip_option))]
     pub honor_labels: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "honorTimestamps")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "honorTimestamps"
+    )]
     #[builder(default, setter(strip_option))]
     pub honor_timestamps: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "httpSDConfigs")]
@@ -150,7 +171,11 @@ pub struct ScrapeConfigSpec {
     )]
     #[builder(default, setter(strip_option))]
     pub light_sail_sd_configs: Option<Vec<ScrapeConfigLightSailSdConfigs>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "linodeSDConfigs")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "linodeSDConfigs"
+    )]
     #[builder(default, setter(strip_option))]
     pub linode_sd_configs: Option<Vec<ScrapeConfigLinodeSdConfigs>>,
     #[serde(
@@ -223,7 +248,9 @@ pub struct ScrapeConfigSpec {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -270,9 +297,13 @@ pub struct ScrapeConfigSpec {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "scrapeInterval")]
     #[builder(default, setter(strip_option))]
     pub scrape_interval: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "scrapeProtocols")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "scrapeProtocols"
+    )]
     #[builder(default, setter(strip_option))]
-    pub scrape_protocols: Option<Vec<String>>,
+    pub scrape_protocols: Option<Vec<ScrapeConfigScrapeProtocols>>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "scrapeTimeout")]
     #[builder(default, setter(strip_option))]
     pub scrape_timeout: Option<String>,
@@ -342,7 +373,11 @@ pub struct ScrapeConfigAzureSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub environment: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -360,7 +395,9 @@ pub struct ScrapeConfigAzureSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigAzureSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigAzureSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -371,7 +408,11 @@ pub struct ScrapeConfigAzureSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "resourceGroup")]
@@ -477,8 +518,9 @@ pub struct ScrapeConfigAzureSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigAzureSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigAzureSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -872,7 +914,11 @@ pub struct ScrapeConfigConsulSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub filter: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -899,7 +945,9 @@ pub struct ScrapeConfigConsulSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigConsulSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigConsulSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -910,7 +958,11 @@ pub struct ScrapeConfigConsulSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -1005,8 +1057,9 @@ pub struct ScrapeConfigConsulSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigConsulSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigConsulSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -1375,7 +1428,11 @@ pub struct ScrapeConfigDigitalOceanSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -1393,8 +1450,9 @@ pub struct ScrapeConfigDigitalOceanSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigDigitalOceanSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigDigitalOceanSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -1405,7 +1463,11 @@ pub struct ScrapeConfigDigitalOceanSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "tlsConfig")]
@@ -1452,8 +1514,9 @@ pub struct ScrapeConfigDigitalOceanSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigDigitalOceanSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigDigitalOceanSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -1593,7 +1656,9 @@ pub struct ScrapeConfigDigitalOceanSdConfigsOauth2TlsConfigCaSecret {
 pub struct ScrapeConfigDigitalOceanSdConfigsOauth2TlsConfigCert {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "configMap")]
     #[builder(default, setter(strip_option))]
-    pub config_map: Option<ScrapeConfigDigitalOceanSdConfigsOauth2TlsConfigCertConfigMap>,
+    pub config_map: Option<
+        ScrapeConfigDigitalOceanSdConfigsOauth2TlsConfigCertConfigMap,
+    >,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub secret: Option<ScrapeConfigDigitalOceanSdConfigsOauth2TlsConfigCertSecret>,
@@ -1802,7 +1867,11 @@ pub struct ScrapeConfigDnsSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub port: Option<i32>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "type")]
@@ -1837,7 +1906,11 @@ pub struct ScrapeConfigDockerSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub filters: Option<Vec<ScrapeConfigDockerSdConfigsFilters>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     pub host: String,
@@ -1870,7 +1943,9 @@ pub struct ScrapeConfigDockerSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigDockerSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigDockerSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -1881,7 +1956,11 @@ pub struct ScrapeConfigDockerSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "tlsConfig")]
@@ -1967,8 +2046,9 @@ pub struct ScrapeConfigDockerSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigDockerSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigDockerSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -2324,7 +2404,11 @@ pub struct ScrapeConfigDockerSwarmSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub filters: Option<Vec<ScrapeConfigDockerSwarmSdConfigsFilters>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     pub host: String,
@@ -2343,8 +2427,9 @@ pub struct ScrapeConfigDockerSwarmSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigDockerSwarmSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigDockerSwarmSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -2355,7 +2440,11 @@ pub struct ScrapeConfigDockerSwarmSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     pub role: ScrapeConfigDockerSwarmSdConfigsRole,
@@ -2442,8 +2531,9 @@ pub struct ScrapeConfigDockerSwarmSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigDockerSwarmSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigDockerSwarmSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -2803,7 +2893,11 @@ pub struct ScrapeConfigEc2SdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub filters: Option<Vec<ScrapeConfigEc2SdConfigsFilters>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -2818,7 +2912,9 @@ pub struct ScrapeConfigEc2SdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigEc2SdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigEc2SdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -2829,7 +2925,11 @@ pub struct ScrapeConfigEc2SdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -3025,7 +3125,11 @@ pub struct ScrapeConfigEurekaSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -3040,7 +3144,9 @@ pub struct ScrapeConfigEurekaSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigEurekaSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigEurekaSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -3051,7 +3157,11 @@ pub struct ScrapeConfigEurekaSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     pub server: String,
@@ -3131,8 +3241,9 @@ pub struct ScrapeConfigEurekaSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigEurekaSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigEurekaSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -3491,7 +3602,11 @@ pub enum ScrapeConfigFallbackScrapeProtocol {
 pub struct ScrapeConfigFileSdConfigs {
     #[builder(default)]
     pub files: Vec<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
 }
@@ -3505,7 +3620,11 @@ pub struct ScrapeConfigGceSdConfigs {
     #[builder(default, setter(strip_option))]
     pub port: Option<i32>,
     pub project: String,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "tagSeparator")]
@@ -3525,7 +3644,11 @@ pub struct ScrapeConfigHetznerSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "labelSelector")]
@@ -3546,7 +3669,9 @@ pub struct ScrapeConfigHetznerSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigHetznerSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigHetznerSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -3557,7 +3682,11 @@ pub struct ScrapeConfigHetznerSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     pub role: ScrapeConfigHetznerSdConfigsRole,
@@ -3637,8 +3766,9 @@ pub struct ScrapeConfigHetznerSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigHetznerSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigHetznerSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -4003,7 +4133,11 @@ pub struct ScrapeConfigHttpSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -4018,7 +4152,9 @@ pub struct ScrapeConfigHttpSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigHttpSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigHttpSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -4029,7 +4165,11 @@ pub struct ScrapeConfigHttpSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "tlsConfig")]
@@ -4109,8 +4249,9 @@ pub struct ScrapeConfigHttpSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigHttpSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigHttpSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -4460,7 +4601,11 @@ pub struct ScrapeConfigIonosSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -4478,7 +4623,9 @@ pub struct ScrapeConfigIonosSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigIonosSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigIonosSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -4489,7 +4636,11 @@ pub struct ScrapeConfigIonosSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "tlsConfig")]
@@ -4536,8 +4687,9 @@ pub struct ScrapeConfigIonosSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigIonosSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigIonosSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -4896,7 +5048,11 @@ pub struct ScrapeConfigKubernetesSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -4914,8 +5070,9 @@ pub struct ScrapeConfigKubernetesSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigKubernetesSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigKubernetesSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -5023,8 +5180,9 @@ pub struct ScrapeConfigKubernetesSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigKubernetesSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigKubernetesSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -5414,7 +5572,11 @@ pub struct ScrapeConfigKumaSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "fetchTimeout")]
     #[builder(default, setter(strip_option))]
     pub fetch_timeout: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -5429,7 +5591,9 @@ pub struct ScrapeConfigKumaSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigKumaSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigKumaSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -5440,7 +5604,11 @@ pub struct ScrapeConfigKumaSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     pub server: String,
@@ -5520,8 +5688,9 @@ pub struct ScrapeConfigKumaSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigKumaSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigKumaSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -5880,7 +6049,11 @@ pub struct ScrapeConfigLightSailSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub endpoint: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -5898,7 +6071,9 @@ pub struct ScrapeConfigLightSailSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigLightSailSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigLightSailSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -5909,7 +6084,11 @@ pub struct ScrapeConfigLightSailSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -6008,8 +6187,9 @@ pub struct ScrapeConfigLightSailSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigLightSailSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigLightSailSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -6370,7 +6550,11 @@ pub struct ScrapeConfigLinodeSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "noProxy")]
@@ -6388,7 +6572,9 @@ pub struct ScrapeConfigLinodeSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigLinodeSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigLinodeSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -6399,7 +6585,11 @@ pub struct ScrapeConfigLinodeSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -6452,8 +6642,9 @@ pub struct ScrapeConfigLinodeSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigLinodeSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigLinodeSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -6892,7 +7083,11 @@ pub struct ScrapeConfigNomadSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -6910,7 +7105,9 @@ pub struct ScrapeConfigNomadSdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigNomadSdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigNomadSdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -6921,7 +7118,11 @@ pub struct ScrapeConfigNomadSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -7007,8 +7208,9 @@ pub struct ScrapeConfigNomadSdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigNomadSdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigNomadSdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -7368,7 +7570,9 @@ pub struct ScrapeConfigOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -7596,7 +7800,9 @@ pub struct ScrapeConfigOpenstackSdConfigs {
         rename = "applicationCredentialSecret"
     )]
     #[builder(default, setter(strip_option))]
-    pub application_credential_secret: Option<ScrapeConfigOpenstackSdConfigsApplicationCredentialSecret>,
+    pub application_credential_secret: Option<
+        ScrapeConfigOpenstackSdConfigsApplicationCredentialSecret,
+    >,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub availability: Option<ScrapeConfigOpenstackSdConfigsAvailability>,
@@ -7606,7 +7812,11 @@ pub struct ScrapeConfigOpenstackSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "domainName")]
     #[builder(default, setter(strip_option))]
     pub domain_name: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "identityEndpoint")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "identityEndpoint"
+    )]
     #[builder(default, setter(strip_option))]
     pub identity_endpoint: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -7621,7 +7831,11 @@ pub struct ScrapeConfigOpenstackSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "projectName")]
     #[builder(default, setter(strip_option))]
     pub project_name: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     pub region: String,
@@ -7818,7 +8032,11 @@ pub struct ScrapeConfigOvhcloudSdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[builder(default, setter(strip_option))]
     pub endpoint: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     pub service: ScrapeConfigOvhcloudSdConfigsService,
@@ -7875,7 +8093,11 @@ pub struct ScrapeConfigPuppetDbsdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(
@@ -7900,7 +8122,9 @@ pub struct ScrapeConfigPuppetDbsdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigPuppetDbsdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigPuppetDbsdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -7912,7 +8136,11 @@ pub struct ScrapeConfigPuppetDbsdConfigs {
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
     pub query: String,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "tlsConfig")]
@@ -7992,8 +8220,9 @@ pub struct ScrapeConfigPuppetDbsdConfigsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header:
-        Option<BTreeMap<String, Vec<ScrapeConfigPuppetDbsdConfigsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigPuppetDbsdConfigsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -8412,7 +8641,11 @@ pub struct ScrapeConfigScalewaySdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "enableHTTP2")]
     #[builder(default, setter(strip_option))]
     pub enable_http2: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "nameFilter")]
@@ -8432,7 +8665,9 @@ pub struct ScrapeConfigScalewaySdConfigs {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ScrapeConfigScalewaySdConfigsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ScrapeConfigScalewaySdConfigsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -8443,7 +8678,11 @@ pub struct ScrapeConfigScalewaySdConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "proxyUrl")]
     #[builder(default, setter(strip_option))]
     pub proxy_url: Option<String>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "refreshInterval")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "refreshInterval"
+    )]
     #[builder(default, setter(strip_option))]
     pub refresh_interval: Option<String>,
     pub role: ScrapeConfigScalewaySdConfigsRole,
@@ -8624,6 +8863,19 @@ pub enum ScrapeConfigScheme {
     Https,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub enum ScrapeConfigScrapeProtocols {
+    PrometheusProto,
+    #[serde(rename = "OpenMetricsText0.0.1")]
+    OpenMetricsText001,
+    #[serde(rename = "OpenMetricsText1.0.0")]
+    OpenMetricsText100,
+    #[serde(rename = "PrometheusText0.0.4")]
+    PrometheusText004,
+    #[serde(rename = "PrometheusText1.0.0")]
+    PrometheusText100,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, TypedBuilder, JsonSchema)]
 pub struct ScrapeConfigStaticConfigs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
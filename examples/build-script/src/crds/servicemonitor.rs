@@ -1,14 +1,15 @@
 // WARNING: generated by kopium - manual changes will be overwritten
-// kopium version: 0.23.0
+// kopium version: 0.24.1
 
 #[allow(unused_imports)]
 mod prelude {
-    pub use k8s_openapi::apimachinery::pkg::{apis::meta::v1::Condition, util::intstr::IntOrString};
     pub use kube::CustomResource;
+    pub use typed_builder::TypedBuilder;
     pub use schemars::JsonSchema;
-    pub use serde::{Deserialize, Serialize};
+    pub use serde::{Serialize, Deserialize};
     pub use std::collections::BTreeMap;
-    pub use typed_builder::TypedBuilder;
+    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+    pub use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
 }
 
 use self::prelude::*;
@@ -93,7 +94,11 @@ pub struct ServiceMonitorSpec {
     )]
     #[builder(default, setter(strip_option))]
     pub native_histogram_min_bucket_factor: Option<IntOrString>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "podTargetLabels")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "podTargetLabels"
+    )]
     #[builder(default, setter(strip_option))]
     pub pod_target_labels: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "sampleLimit")]
@@ -109,9 +114,13 @@ pub struct ServiceMonitorSpec {
     )]
     #[builder(default, setter(strip_option))]
     pub scrape_classic_histograms: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "scrapeProtocols")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "scrapeProtocols"
+    )]
     #[builder(default, setter(strip_option))]
-    pub scrape_protocols: Option<Vec<String>>,
+    pub scrape_protocols: Option<Vec<ServiceMonitorScrapeProtocols>>,
     pub selector: ServiceMonitorSelector,
     #[serde(
         default,
@@ -150,7 +159,11 @@ pub struct ServiceMonitorEndpoints {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "basicAuth")]
     #[builder(default, setter(strip_option))]
     pub basic_auth: Option<ServiceMonitorEndpointsBasicAuth>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "bearerTokenFile")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "bearerTokenFile"
+    )]
     #[builder(default, setter(strip_option))]
     pub bearer_token_file: Option<String>,
     #[serde(
@@ -166,13 +179,21 @@ pub struct ServiceMonitorEndpoints {
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "filterRunning")]
     #[builder(default, setter(strip_option))]
     pub filter_running: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "followRedirects")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "followRedirects"
+    )]
     #[builder(default, setter(strip_option))]
     pub follow_redirects: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "honorLabels")]
     #[builder(default, setter(strip_option))]
     pub honor_labels: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "honorTimestamps")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "honorTimestamps"
+    )]
     #[builder(default, setter(strip_option))]
     pub honor_timestamps: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -206,7 +227,9 @@ pub struct ServiceMonitorEndpoints {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ServiceMonitorEndpointsProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ServiceMonitorEndpointsProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -390,7 +413,9 @@ pub struct ServiceMonitorEndpointsOauth2 {
         rename = "proxyConnectHeader"
     )]
     #[builder(default, setter(strip_option))]
-    pub proxy_connect_header: Option<BTreeMap<String, Vec<ServiceMonitorEndpointsOauth2ProxyConnectHeader>>>,
+    pub proxy_connect_header: Option<
+        BTreeMap<String, Vec<ServiceMonitorEndpointsOauth2ProxyConnectHeader>>,
+    >,
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -839,9 +864,26 @@ pub struct ServiceMonitorNamespaceSelector {
     pub match_names: Option<Vec<String>>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub enum ServiceMonitorScrapeProtocols {
+    PrometheusProto,
+    #[serde(rename = "OpenMetricsText0.0.1")]
+    OpenMetricsText001,
+    #[serde(rename = "OpenMetricsText1.0.0")]
+    OpenMetricsText100,
+    #[serde(rename = "PrometheusText0.0.4")]
+    PrometheusText004,
+    #[serde(rename = "PrometheusText1.0.0")]
+    PrometheusText100,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, TypedBuilder, JsonSchema)]
 pub struct ServiceMonitorSelector {
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "matchExpressions")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "matchExpressions"
+    )]
     #[builder(default, setter(strip_option))]
     pub match_expressions: Option<Vec<ServiceMonitorSelectorMatchExpressions>>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "matchLabels")]
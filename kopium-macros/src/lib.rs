@@ -0,0 +1,304 @@
+//! Generate rust types for kubernetes CRDs at compile time
+//!
+//! This crate provides [`include_crd!`], which runs the same generator as the `kopium` cli
+//! against a CRD file in your crate, and expands to the generated types.
+//!
+//! ```ignore
+//! mod prometheusrule {
+//!     kopium_macros::include_crd!(
+//!         "crds/prometheusrules.monitoring.coreos.com.yaml",
+//!         schema = "derived",
+//!         derive = ["PartialEq", "@struct=Default"],
+//!         smart_derive_elision,
+//!         docs,
+//!     );
+//! }
+//! ```
+use std::path::PathBuf;
+
+use kopium::{
+    DateTimeBackend, Derive, EmbeddedResources, FormatType, MapType, NamingStrategy, Rename, SchemaMode,
+    TypeGenerator, TypeOverride,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident, LitBool, LitStr, Token,
+};
+
+/// Generate rust types for the CRD(s) in a file
+///
+/// The path is relative to the `CARGO_MANIFEST_DIR` of the crate invoking the macro. Files with
+/// multiple CRDs expand to one module per CRD, named after its lowercased kind. Directories are rejected, as
+/// cargo cannot track the files added to them; generate those with `kopium::build::Builder` instead.
+///
+/// The generated prelude is a module named `prelude`, so invoke this macro in a dedicated module.
+///
+/// The path can be followed by these options, corresponding to the [`TypeGenerator`] options of the cli:
+///
+/// - `api_version = "v1"`: generate this CRD version rather than the latest
+/// - `all_versions`: generate all served CRD versions, each in its own module
+/// - `derive = ["PartialEq", "@struct=Default"]`: derive additional traits, in the syntax of `--derive`
+/// - `schema = "derived"`: the kube schema mode (`manual`, `derived`, `preserved` or `disabled`); `derived` implies `derive = ["JsonSchema"]`
+/// - `docs`: emit doc comments from descriptions
+/// - `builders`: emit builder derives via the `typed-builder` crate
+/// - `defaults`: emit schema defaults
+/// - `validate`: emit `Validate` impls checking schema constraints (requires the `regex` crate for patterns)
+/// - `validate_cel`: emit `ValidateCel` impls evaluating `x-kubernetes-validations` rules (requires the `cel` crate)
+/// - `map_type = "HashMap"`: the type of maps (`BTreeMap` or `HashMap`)
+/// - `embedded_resources = "generated"`: the type of embedded resources (`dynamic` or `generated`)
+/// - `datetime = "jiff"`: the type of dates and date-times (`string`, `chrono`, `jiff`, `time` or `k8s`)
+/// - `explicit_null`: tell explicit nulls apart from absent values for optional nullable fields, as `Option<Option<T>>`
+/// - `list_types`: emit `BTreeSet`s for lists of type `set`, and accessors for lists of type `map`
+/// - `string_formats`: use types for strings with well-known formats (e.g. `std::net::Ipv4Addr` for `ipv4`)
+/// - `format_types = ["uuid=uuid::Uuid"]`: use these types for strings with a format, in the syntax of `--format-type`
+/// - `naming = "leaf"`: how to name generated types (`full`, `suffix`, `leaf` or `title`)
+/// - `elide = ["FooBar"]`: elide these containers from the output
+/// - `type_overrides = ["spec.template=my_crate::Template"]`: use these types for properties, in the syntax of `--type-override`
+/// - `renames = ["FooConfig=Config", "FooConfig.tlsConfig=tls"]`: rename types, fields or variants, in the syntax of `--rename`
+/// - `well_known_types`: use common k8s_openapi types (e.g. `Toleration`) for schemas shaped like them
+/// - `no_known_types = ["Toleration"]`: generate structs for these well-known k8s_openapi types
+/// - `hide_prelude`, `hide_kube`, `preserve_metadata`, `relaxed`, `no_condition`, `no_object_reference`,
+///   `smart_derive_elision`, `dedup_types`: as their cli counterparts
+///
+/// Flags can also be given as `docs = false`.
+#[proc_macro]
+pub fn include_crd(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as IncludeCrd);
+
+    match input.expand() {
+        Ok(tokens) => tokens.into(),
+        Err(error) => syn::Error::new(input.path.span(), format!("{:#}", error))
+            .to_compile_error()
+            .into(),
+    }
+}
+
+struct IncludeCrd {
+    path: LitStr,
+    generator: TypeGenerator,
+}
+
+impl IncludeCrd {
+    fn expand(&self) -> anyhow::Result<TokenStream> {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")?;
+        let path = PathBuf::from(manifest_dir).join(self.path.value());
+        // cargo can only track files, through the include_bytes! below
+        if path.is_dir() {
+            anyhow::bail!(
+                "{} is a directory; include one file per macro, or generate directories with kopium::build::Builder",
+                path.display()
+            );
+        }
+
+        let crds = kopium::crds_from_path(&path)?;
+        let mut generator = self.generator.clone();
+        if generator.schema_mode == SchemaMode::Preserved {
+            generator.original_schemas = kopium::schemas_from_path(&path)?;
+        }
+        let generated = match crds.as_slice() {
+            [] => anyhow::bail!("No CustomResourceDefinitions found in {}", path.display()),
+            [crd] => generator.generate_rust_types_for(crd, None::<String>)?,
+            crds => generator.generate_rust_types_for_all(crds, None::<String>)?,
+        };
+
+        let types = generated
+            .parse::<TokenStream>()
+            .map_err(|error| anyhow::anyhow!("generated invalid tokens: {}", error))?;
+
+        // makes cargo recompile the invoking crate when the crd changes
+        let path = path.display().to_string();
+        Ok(quote! {
+            const _: &[u8] = include_bytes!(#path);
+            #types
+        })
+    }
+}
+
+impl Parse for IncludeCrd {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut generator = TypeGenerator::default();
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "api_version" => generator.api_version = Some(parse_str(input)?.value()),
+                "all_versions" => generator.all_versions = parse_flag(input)?,
+                "hide_prelude" => generator.hide_prelude = parse_flag(input)?,
+                "hide_kube" => generator.hide_kube = parse_flag(input)?,
+                "docs" => generator.emit_docs = parse_flag(input)?,
+                "defaults" => generator.emit_defaults = parse_flag(input)?,
+                "validate" => generator.validate = parse_flag(input)?,
+                "validate_cel" => generator.validate_cel = parse_flag(input)?,
+                "preserve_metadata" => generator.preserve_metadata = parse_flag(input)?,
+                "builders" => generator.builders = parse_flag(input)?,
+                "relaxed" => generator.relaxed = parse_flag(input)?,
+                "explicit_null" => generator.explicit_null = parse_flag(input)?,
+                "no_condition" => generator.no_condition = parse_flag(input)?,
+                "no_object_reference" => generator.no_object_reference = parse_flag(input)?,
+                "smart_derive_elision" => generator.smart_derive_elision = parse_flag(input)?,
+                "dedup_types" => generator.dedup_types = parse_flag(input)?,
+                "list_types" => generator.list_types = parse_flag(input)?,
+                "string_formats" => generator.string_formats = parse_flag(input)?,
+                "well_known_types" => generator.well_known_types = parse_flag(input)?,
+                "schema" => {
+                    let mode = parse_str(input)?;
+                    generator.schema_mode = match mode.value().to_lowercase().as_str() {
+                        "manual" => SchemaMode::Manual,
+                        "derived" => SchemaMode::Derived,
+                        "preserved" => SchemaMode::Preserved,
+                        "disabled" => SchemaMode::Disabled,
+                        _ => {
+                            return Err(syn::Error::new(
+                                mode.span(),
+                                "expected one of `manual`, `derived`, `preserved` or `disabled`",
+                            ))
+                        }
+                    };
+                }
+                "map_type" => {
+                    let map = parse_str(input)?;
+                    generator.map_type = match map.value().as_str() {
+                        "BTreeMap" => MapType::BTreeMap,
+                        "HashMap" => MapType::HashMap,
+                        _ => return Err(syn::Error::new(map.span(), "expected `BTreeMap` or `HashMap`")),
+                    };
+                }
+                "embedded_resources" => {
+                    let embedded = parse_str(input)?;
+                    generator.embedded_resources = match embedded.value().to_lowercase().as_str() {
+                        "dynamic" => EmbeddedResources::Dynamic,
+                        "generated" => EmbeddedResources::Generated,
+                        _ => {
+                            return Err(syn::Error::new(
+                                embedded.span(),
+                                "expected `dynamic` or `generated`",
+                            ))
+                        }
+                    };
+                }
+                "datetime" => {
+                    let datetime = parse_str(input)?;
+                    generator.datetime = match datetime.value().to_lowercase().as_str() {
+                        "string" => DateTimeBackend::String,
+                        "chrono" => DateTimeBackend::Chrono,
+                        "jiff" => DateTimeBackend::Jiff,
+                        "time" => DateTimeBackend::Time,
+                        "k8s" => DateTimeBackend::K8s,
+                        _ => {
+                            return Err(syn::Error::new(
+                                datetime.span(),
+                                "expected one of `string`, `chrono`, `jiff`, `time` or `k8s`",
+                            ))
+                        }
+                    };
+                }
+                "naming" => {
+                    let naming = parse_str(input)?;
+                    generator.naming = match naming.value().to_lowercase().as_str() {
+                        "full" => NamingStrategy::Full,
+                        "suffix" => NamingStrategy::Suffix,
+                        "leaf" => NamingStrategy::Leaf,
+                        "title" => NamingStrategy::Title,
+                        _ => {
+                            return Err(syn::Error::new(
+                                naming.span(),
+                                "expected one of `full`, `suffix`, `leaf` or `title`",
+                            ))
+                        }
+                    };
+                }
+                "derive" => {
+                    for derive in parse_str_list(input)? {
+                        let value = derive
+                            .value()
+                            .parse::<Derive>()
+                            .map_err(|error| syn::Error::new(derive.span(), error))?;
+                        if !generator.derive_traits.contains(&value) {
+                            generator.derive_traits.push(value);
+                        }
+                    }
+                }
+                "elide" => {
+                    let elide = parse_str_list(input)?;
+                    generator.elide.extend(elide.iter().map(LitStr::value));
+                }
+                "type_overrides" => {
+                    for type_override in parse_str_list(input)? {
+                        let value = type_override
+                            .value()
+                            .parse::<TypeOverride>()
+                            .map_err(|error| syn::Error::new(type_override.span(), error))?;
+                        generator.type_overrides.push(value);
+                    }
+                }
+                "format_types" => {
+                    for format_type in parse_str_list(input)? {
+                        let value = format_type
+                            .value()
+                            .parse::<FormatType>()
+                            .map_err(|error| syn::Error::new(format_type.span(), error))?;
+                        generator.format_overrides.push(value);
+                    }
+                }
+                "renames" => {
+                    for rename in parse_str_list(input)? {
+                        let value = rename
+                            .value()
+                            .parse::<Rename>()
+                            .map_err(|error| syn::Error::new(rename.span(), error))?;
+                        generator.renames.push(value);
+                    }
+                }
+                "no_known_types" => {
+                    let names = parse_str_list(input)?;
+                    generator.no_known_types.extend(names.iter().map(LitStr::value));
+                }
+                _ => return Err(syn::Error::new(key.span(), format!("unknown option `{}`", key))),
+            }
+        }
+
+        // mirrors the cli, where --schema=derived implies --derive JsonSchema
+        if generator.schema_mode == SchemaMode::Derived {
+            let json_schema = Derive::all("JsonSchema");
+            if !generator.derive_traits.contains(&json_schema) {
+                generator.derive_traits.push(json_schema);
+            }
+        }
+
+        Ok(Self { path, generator })
+    }
+}
+
+fn parse_str(input: ParseStream) -> syn::Result<LitStr> {
+    input.parse::<Token![=]>()?;
+    input.parse()
+}
+
+fn parse_str_list(input: ParseStream) -> syn::Result<Vec<LitStr>> {
+    input.parse::<Token![=]>()?;
+    let content;
+    bracketed!(content in input);
+    let list = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+    Ok(list.into_iter().collect())
+}
+
+/// A flag is either given bare, or with an explicit `= true` / `= false`
+fn parse_flag(input: ParseStream) -> syn::Result<bool> {
+    if input.peek(Token![=]) {
+        input.parse::<Token![=]>()?;
+        Ok(input.parse::<LitBool>()?.value)
+    } else {
+        Ok(true)
+    }
+}
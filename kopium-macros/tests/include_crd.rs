@@ -0,0 +1,604 @@
+use kube::Resource;
+
+mod multi {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/multi-document.clux.dev.yaml",
+        derive = ["PartialEq"],
+    );
+}
+
+mod versions {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/multiversions.clux.dev.yaml",
+        all_versions,
+        docs,
+    );
+}
+
+mod proxy {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/proxies.clux.dev.yaml",
+        validate,
+        validate_cel,
+    );
+}
+
+mod preserved {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/proxies.clux.dev.yaml",
+        schema = "preserved",
+    );
+}
+
+mod overridden {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/proxies.clux.dev.yaml",
+        type_overrides = ["spec.endpoints=crate::Endpoint"],
+    );
+}
+
+mod gateway {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/gateways.clux.dev.yaml",
+        naming = "leaf",
+        dedup_types,
+        renames = ["GatewayTlsConfig=Tls", "GatewayListeners.port=listen_port"],
+    );
+}
+
+mod backend {
+    kopium_macros::include_crd!("../tests/cmd/generate/crds/backends.clux.dev.yaml", list_types,);
+}
+
+mod network {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/networks.clux.dev.yaml",
+        string_formats,
+        format_types = ["hostname=String"],
+    );
+}
+
+mod validated_network {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/networks.clux.dev.yaml",
+        string_formats,
+        validate,
+    );
+}
+
+mod defaulted_network {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/networks.clux.dev.yaml",
+        string_formats,
+        defaults,
+    );
+}
+
+mod setting {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/settings.clux.dev.yaml",
+        preserve_metadata,
+    );
+}
+
+mod schedule {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/schedules.clux.dev.yaml",
+        datetime = "k8s",
+    );
+}
+
+mod rollout {
+    kopium_macros::include_crd!("../tests/cmd/generate/crds/rollouts.clux.dev.yaml", explicit_null,);
+}
+
+mod validated_rollout {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/rollouts.clux.dev.yaml",
+        explicit_null,
+        validate_cel,
+    );
+}
+
+mod route {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/routes.clux.dev.yaml",
+        derive = ["PartialEq"],
+        defaults,
+    );
+}
+
+mod redirect {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/redirects.clux.dev.yaml",
+        derive = ["Default", "PartialEq"],
+        defaults,
+    );
+}
+
+mod composition {
+    kopium_macros::include_crd!(
+        "../tests/cmd/generate/crds/compositions.clux.dev.yaml",
+        schema = "derived",
+        docs,
+    );
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct Endpoint {
+    pub port: u16,
+}
+
+#[test]
+fn generates_a_module_per_crd() {
+    let spec = multi::foo::FooSpec { name: "foo".into() };
+    assert_eq!(spec, multi::foo::FooSpec { name: "foo".into() });
+
+    let foo = multi::foo::Foo::new("example", spec);
+    assert_eq!(serde_json::to_value(&foo).unwrap()["spec"]["name"], "foo");
+    assert_eq!(multi::bar::Bar::kind(&()), "Bar");
+}
+
+#[test]
+#[allow(deprecated)]
+fn generates_all_versions() {
+    assert_eq!(versions::v1::MultiVersion::version(&()), "v1");
+    assert_eq!(versions::v1beta1::MultiVersion::version(&()), "v1beta1");
+
+    let spec = versions::v1::MultiVersionSpec {
+        name: "foo".into(),
+        replicas: Some(2),
+    };
+    assert_eq!(serde_json::to_value(&spec).unwrap()["replicas"], 2);
+}
+
+#[test]
+fn validates_schema_constraints() {
+    use proxy::{Proxy, ProxyEndpoints, ProxySpec, ProxyStatus, Validate};
+
+    let endpoint = |port| ProxyEndpoints { host: None, port };
+    let mut proxy = Proxy::new("example", ProxySpec {
+        endpoints: vec![endpoint(80), endpoint(8080)],
+        headers: None,
+        methods: Some(vec!["GET".into(), "POST".into()]),
+        namespace: Some("kube-system".into()),
+        prefix: Some("/api".into()),
+        replicas: Some(3),
+        weight: Some(1.5),
+    });
+    proxy.status = Some(ProxyStatus {
+        ready_replicas: Some(3),
+    });
+    assert!(proxy.validate().is_ok());
+
+    proxy.spec.endpoints.push(endpoint(0));
+    proxy.spec.methods = Some(vec!["GET".into(), "GET".into()]);
+    proxy.spec.prefix = Some("api".into());
+    proxy.spec.weight = Some(0.75);
+    proxy.status = Some(ProxyStatus {
+        ready_replicas: Some(-1),
+    });
+
+    let errors = proxy.validate().unwrap_err();
+    let paths = errors
+        .0
+        .iter()
+        .map(|error| error.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        "spec.endpoints[2].port",
+        "spec.methods",
+        "spec.prefix",
+        "spec.weight",
+        "status.readyReplicas",
+    ]);
+    assert_eq!(errors.0[0].message, "must be greater than or equal to 1");
+
+    // the spec validates on its own, relative to itself
+    let errors = proxy.spec.validate().unwrap_err();
+    assert_eq!(errors.0[0].path, "endpoints[2].port");
+}
+
+#[test]
+fn evaluates_validation_rules() {
+    use proxy::{Proxy, ProxyEndpoints, ProxySpec, ValidateCel};
+
+    let mut proxy = Proxy::new("example", ProxySpec {
+        endpoints: vec![ProxyEndpoints {
+            host: Some("example.com".into()),
+            port: 443,
+        }],
+        headers: None,
+        methods: None,
+        namespace: Some("default".into()),
+        prefix: Some("/api".into()),
+        replicas: None,
+        weight: None,
+    });
+    assert!(proxy.validate_cel(None).is_ok());
+
+    let old = proxy.clone();
+    proxy.spec.endpoints[0].port = 22;
+    proxy.spec.namespace = Some("kube-system".into());
+    proxy.spec.prefix = Some("/v2".into());
+    proxy.spec.weight = Some(1.0);
+
+    // transition rules are only evaluated against an old value
+    let errors = proxy.validate_cel(None).unwrap_err();
+    let messages = errors
+        .0
+        .iter()
+        .map(|error| format!("{}: {}", error.path, error.message))
+        .collect::<Vec<_>>();
+    assert_eq!(messages, vec![
+        "spec.weight: weight requires replicas",
+        "spec.endpoints[0]: port 22 is not allowed for example.com",
+        "spec.namespace: must not be a system namespace",
+    ]);
+
+    let errors = proxy.validate_cel(Some(&old)).unwrap_err();
+    assert_eq!(errors.0.len(), 4);
+    assert_eq!(errors.0[3].path, "spec.prefix");
+    assert_eq!(errors.0[3].message, "prefix is immutable");
+
+    proxy.metadata.name = Some("a".repeat(64));
+    let errors = proxy.validate_cel(None).unwrap_err();
+    assert_eq!(errors.0[0].path, "");
+    assert_eq!(errors.0[0].message, "name must be at most 63 characters long");
+}
+
+#[test]
+fn preserves_the_original_schema() {
+    use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+        CustomResourceDefinition, JSONSchemaProps,
+    };
+    use kube::CustomResourceExt;
+
+    let upstream: CustomResourceDefinition = serde_yaml::from_str(include_str!(
+        "../../tests/cmd/generate/crds/proxies.clux.dev.yaml"
+    ))
+    .unwrap();
+    let mut upstream = upstream.spec.versions[0]
+        .schema
+        .clone()
+        .unwrap()
+        .open_api_v3_schema
+        .unwrap();
+
+    let crd = preserved::Proxy::crd();
+    let mut schema = crd.spec.versions[0]
+        .schema
+        .clone()
+        .unwrap()
+        .open_api_v3_schema
+        .unwrap();
+
+    // kube-derive titles the root schema, and drops `uniqueItems` as it is not structural
+    assert_eq!(schema.title.take().as_deref(), Some("Proxy"));
+    let methods = |schema: &mut JSONSchemaProps| {
+        let spec = schema.properties.as_mut().unwrap().get_mut("spec").unwrap();
+        spec.properties
+            .as_mut()
+            .unwrap()
+            .get_mut("methods")
+            .unwrap()
+            .unique_items
+            .take()
+    };
+    assert_eq!(methods(&mut upstream), Some(true));
+    assert_eq!(methods(&mut schema), None);
+    assert_eq!(schema, upstream);
+}
+
+#[test]
+fn keeps_integer_bounds_of_the_original_schema() {
+    use schemars::JsonSchema;
+
+    let schema = preserved::ProxySpec::json_schema(&mut schemars::SchemaGenerator::default());
+    let port = &schema.as_value()["properties"]["endpoints"]["items"]["properties"]["port"];
+    assert_eq!(port["maximum"], serde_json::json!(65535));
+    assert!(port["maximum"].is_u64());
+    assert!(port["minimum"].is_u64());
+}
+
+#[test]
+fn overrides_types() {
+    let spec: overridden::ProxySpec = serde_json::from_value(serde_json::json!({
+        "endpoints": [{"host": "example.com", "port": 8080}],
+    }))
+    .unwrap();
+    assert_eq!(spec.endpoints[0].port, 8080);
+}
+
+#[test]
+fn names_merges_and_renames_types() {
+    use gateway::{GatewayListeners, GatewayPassword, GatewayUpstream, Tls};
+
+    let upstream = GatewayUpstream {
+        basic_auth: None,
+        tls_config: Some(Tls {
+            ca: Some(GatewayPassword {
+                key: Some("ca.crt".into()),
+                name: Some("upstream-ca".into()),
+            }),
+            insecure_skip_verify: None,
+        }),
+        url: None,
+    };
+    // identical objects share a type, so values move between them as is
+    let listener = GatewayListeners {
+        listen_port: Some(443),
+        tls_config: upstream.tls_config.clone(),
+    };
+    let json = serde_json::to_value(&listener).unwrap();
+    assert_eq!(json["tlsConfig"]["ca"]["name"], "upstream-ca");
+    assert_eq!(json["port"], 443);
+}
+
+#[test]
+fn honors_list_types() {
+    use backend::{BackendEndpoints, BackendPorts, BackendSpec};
+
+    let mut spec: BackendSpec = serde_json::from_value(serde_json::json!({
+        "endpoints": [{"name": "a", "address": "10.0.0.1"}],
+        "hosts": ["b.example.com", "a.example.com", "a.example.com"],
+    }))
+    .unwrap();
+    let hosts = spec.hosts.clone().unwrap().into_iter().collect::<Vec<_>>();
+    assert_eq!(hosts, ["a.example.com", "b.example.com"]);
+
+    let endpoint = |address: &str| BackendEndpoints {
+        address: Some(address.into()),
+        name: "a".into(),
+    };
+    let replaced = spec.upsert_endpoints(endpoint("10.0.0.2"));
+    assert_eq!(replaced.unwrap().address.as_deref(), Some("10.0.0.1"));
+    assert_eq!(spec.endpoints.len(), 1);
+    spec.get_endpoints_by_name_mut("a").unwrap().address = None;
+    assert!(spec.get_endpoints_by_name("a").unwrap().address.is_none());
+
+    // optional lists are created by the first upsert
+    let port = BackendPorts {
+        container_port: 8080,
+        protocol: Some("TCP".into()),
+    };
+    assert!(spec.upsert_ports(port).is_none());
+    assert!(spec
+        .get_ports_by_container_port_and_protocol(8080, None)
+        .is_none());
+    let removed = spec.remove_ports_by_container_port_and_protocol(8080, Some("TCP"));
+    assert_eq!(removed.unwrap().container_port, 8080);
+    assert_eq!(spec.ports.as_deref().map(<[_]>::len), Some(0));
+}
+
+#[test]
+fn types_string_formats() {
+    use std::{net::Ipv4Addr, time::Duration};
+
+    let spec: network::NetworkSpec = serde_json::from_value(serde_json::json!({
+        "id": "f81d4fae-7dec-11d0-a765-00a0c91e6bf6",
+        "caBundle": "Y2E=",
+        "dnsServers": ["10.0.0.53"],
+        "hostname": "example.com",
+        "leaseTime": "1h30m",
+        "limits": {"bandwidth": "100Mi"},
+    }))
+    .unwrap();
+    assert_eq!(spec.id.to_string(), "f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+    assert_eq!(spec.ca_bundle.unwrap().0, b"ca");
+    assert_eq!(spec.dns_servers.unwrap(), [Ipv4Addr::new(10, 0, 0, 53)]);
+    assert_eq!(spec.hostname.as_deref(), Some("example.com"));
+    assert_eq!(spec.lease_time.unwrap().to_std(), Some(Duration::from_secs(5400)));
+    assert_eq!(spec.limits.unwrap()["bandwidth"].0, "100Mi");
+
+    let lease = |value: &str| network::Duration(value.into()).to_std();
+    assert_eq!(lease("1.5s"), Some(Duration::from_millis(1500)));
+    assert_eq!(lease("0"), Some(Duration::ZERO));
+    assert_eq!(lease("-1s"), None);
+    assert_eq!(lease("10"), None);
+
+    let invalid = serde_json::json!({"id": "f81d4fae", "gateway": "not an address"});
+    assert!(serde_json::from_value::<network::NetworkSpec>(invalid).is_err());
+}
+
+#[test]
+fn defaults_string_formats() {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    let spec: defaulted_network::NetworkSpec =
+        serde_json::from_value(serde_json::json!({"id": "f81d4fae-7dec-11d0-a765-00a0c91e6bf6"})).unwrap();
+    assert_eq!(spec.bandwidth.unwrap().0, "1Gi");
+    assert_eq!(spec.ca_bundle, None);
+    assert_eq!(spec.dns_servers.unwrap(), [Ipv4Addr::new(10, 0, 0, 53)]);
+    assert_eq!(spec.gateway_v6, Some(Ipv6Addr::LOCALHOST));
+    assert_eq!(spec.hostname.unwrap().0, "example.com");
+    assert_eq!(spec.lease_time.unwrap().0, "30s");
+}
+
+#[test]
+fn validates_string_format_newtypes() {
+    use validated_network::{Cidr, Hostname, NetworkSpec, Validate};
+
+    let mut spec: NetworkSpec = serde_json::from_value(serde_json::json!({
+        "id": "f81d4fae-7dec-11d0-a765-00a0c91e6bf6",
+        "cidr": "10.0.0.0/8",
+        "hostname": "example.com",
+    }))
+    .unwrap();
+    assert!(spec.validate().is_ok());
+
+    spec.cidr = Some(Cidr("192.168.0.0/16".into()));
+    spec.hostname = Some(Hostname("a".repeat(254)));
+    let errors = spec.validate().unwrap_err();
+    let paths = errors
+        .0
+        .iter()
+        .map(|error| error.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["cidr", "hostname"]);
+}
+
+#[test]
+fn types_datetimes() {
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    let spec: schedule::ScheduleSpec = serde_json::from_value(serde_json::json!({
+        "startTime": "2024-01-01T09:00:00Z",
+        "holidays": ["2024-12-25"],
+    }))
+    .unwrap();
+    let start: Time = serde_json::from_value(serde_json::json!("2024-01-01T09:00:00Z")).unwrap();
+    assert_eq!(spec.start_time, start);
+    assert_eq!(spec.holidays.unwrap(), ["2024-12-25"]);
+
+    let invalid = serde_json::json!({"startTime": "tomorrow"});
+    assert!(serde_json::from_value::<schedule::ScheduleSpec>(invalid).is_err());
+}
+
+#[test]
+fn tells_explicit_nulls_apart() {
+    let patch = serde_json::json!({
+        "image": "app:v2",
+        "pausedAt": null,
+        "replicas": null,
+    });
+    let spec: rollout::RolloutSpec = serde_json::from_value(patch.clone()).unwrap();
+    assert_eq!(spec.paused_at, None);
+    assert_eq!(spec.replicas, Some(None));
+    assert!(spec.strategy.is_none());
+    // the patch round-trips, unsetting replicas but leaving the strategy alone
+    assert_eq!(serde_json::to_value(&spec).unwrap(), patch);
+
+    // required nullable fields are always sent, as null when unset
+    let spec: rollout::RolloutSpec = serde_json::from_value(serde_json::json!({"image": "app:v2"})).unwrap();
+    let sent = serde_json::to_value(&spec).unwrap();
+    assert_eq!(sent, serde_json::json!({"image": "app:v2", "pausedAt": null}));
+}
+
+#[test]
+fn evaluates_rules_of_explicitly_nullable_objects_once() {
+    use validated_rollout::{RolloutSpec, ValidateCel};
+
+    let spec: RolloutSpec = serde_json::from_value(serde_json::json!({
+        "image": "app:v2",
+        "strategy": {},
+    }))
+    .unwrap();
+    let errors = spec.validate_cel(None).unwrap_err();
+    let messages = errors
+        .0
+        .iter()
+        .map(|error| format!("{}: {}", error.path, error.message))
+        .collect::<Vec<_>>();
+    assert_eq!(messages, vec!["strategy: maxSurge must be set"]);
+
+    let spec: RolloutSpec =
+        serde_json::from_value(serde_json::json!({"image": "app:v2", "strategy": null})).unwrap();
+    assert!(spec.validate_cel(None).is_ok());
+}
+
+#[test]
+fn round_trips_untagged_alternatives() {
+    use setting::{SettingSpec, SettingValue};
+
+    // derived structs also deserialize from sequences, so arrays must be tried before objects
+    for value in [
+        serde_json::json!({"value": ["x"]}),
+        serde_json::json!({"value": {"a": "x"}}),
+        serde_json::json!({"value": "x"}),
+        serde_json::json!({"value": 1}),
+    ] {
+        let spec: SettingSpec = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&spec).unwrap(), value);
+    }
+    let spec: SettingSpec = serde_json::from_value(serde_json::json!({"value": ["x"]})).unwrap();
+    assert!(matches!(spec.value, SettingValue::Array(_)));
+}
+
+#[test]
+fn preserves_metadata_with_quotes_and_backslashes() {
+    use kube::CustomResourceExt;
+
+    let crd = setting::Setting::crd();
+    let annotations = crd.metadata.annotations.unwrap();
+    assert_eq!(annotations["clux.dev/greeting"], r#"say "hi" \o/"#);
+    assert_eq!(
+        annotations["kubectl.kubernetes.io/last-applied-configuration"],
+        r#"{"kind":"CustomResourceDefinition"}"#
+    );
+    assert_eq!(crd.metadata.labels.unwrap()["clux.dev/path"], r"C:\settings");
+}
+
+#[test]
+fn serializes_enums_by_value() {
+    use route::{RouteHeaders, RouteMethods, RoutePriority, RouteRedirectCode};
+
+    let value = serde_json::json!({
+        "redirectCode": 307,
+        "priority": -1,
+        "methods": ["GET", "DELETE"],
+        "headers": {"x-forwarded-for": "Remove"},
+    });
+    let spec: route::RouteSpec = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(spec.redirect_code, RouteRedirectCode::Value307);
+    assert_eq!(spec.priority, Some(RoutePriority::ValueMinus1));
+    assert_eq!(
+        spec.methods.as_deref(),
+        Some(&[RouteMethods::Get, RouteMethods::Delete][..])
+    );
+    assert_eq!(
+        spec.headers.as_ref().unwrap()["x-forwarded-for"],
+        RouteHeaders::Remove
+    );
+    assert_eq!(serde_json::to_value(&spec).unwrap(), value);
+
+    // the schema default names a variant by its value
+    let spec: route::RouteSpec = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert_eq!(spec.redirect_code, RouteRedirectCode::Value302);
+
+    let invalid = serde_json::json!({"redirectCode": 303});
+    assert!(serde_json::from_value::<route::RouteSpec>(invalid).is_err());
+    let invalid = serde_json::json!({"redirectCode": "301"});
+    assert!(serde_json::from_value::<route::RouteSpec>(invalid).is_err());
+}
+
+#[test]
+fn defaults_lists_of_objects_and_enums() {
+    use redirect::{RedirectMethods, RedirectRules, RedirectRulesPath, RedirectRulesPathType, RedirectSpec};
+
+    let spec: RedirectSpec = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert_eq!(spec, RedirectSpec::default());
+    assert_eq!(
+        spec.methods.as_deref(),
+        Some(&[RedirectMethods::Get, RedirectMethods::Head][..])
+    );
+    assert_eq!(spec.rules.unwrap(), [RedirectRules {
+        path: RedirectRulesPath {
+            r#type: Some(RedirectRulesPathType::PathPrefix),
+            value: Some("/".into()),
+        },
+        target: None,
+    }]);
+}
+
+#[test]
+fn derives_schemas_of_dynamic_embedded_resources() {
+    use kube::CustomResourceExt;
+
+    let spec: composition::CompositionSpec = serde_json::from_value(serde_json::json!({
+        "resources": [{"base": {"apiVersion": "v1", "kind": "ConfigMap", "metadata": {"name": "a"}}}],
+    }))
+    .unwrap();
+    assert_eq!(spec.resources[0].base.types.as_ref().unwrap().kind, "ConfigMap");
+
+    let crd = composition::Composition::crd();
+    let schema = crd.spec.versions[0].schema.as_ref().unwrap();
+    let spec = &schema
+        .open_api_v3_schema
+        .as_ref()
+        .unwrap()
+        .properties
+        .as_ref()
+        .unwrap()["spec"];
+    assert!(spec.properties.as_ref().unwrap().contains_key("template"));
+}
@@ -6,24 +6,115 @@ use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
     JSONSchemaProps, JSONSchemaPropsOrArray, JSONSchemaPropsOrBool, JSON,
 };
 
-use crate::{Container, MapType, Member, Output};
+use crate::{
+    naming::is_type_name,
+    output::{integer_fits, rust_name},
+    Constraints, Container, DateTimeBackend, EmbeddedResources, FormatTypes, KnownTypes, ListType, MapType,
+    Member, Output, TypeOverride,
+};
 
 const IGNORED_KEYS: [&str; 3] = ["metadata", "apiVersion", "kind"];
 
-#[derive(Default)]
 pub struct Config {
+    #[deprecated(note = "remove `Condition` from `known_types`")]
     pub no_condition: bool,
+    #[deprecated(note = "remove `ObjectReference` from `known_types`")]
     pub no_object_reference: bool,
+    pub known_types: KnownTypes,
+    pub format_types: FormatTypes,
+    pub type_overrides: Vec<TypeOverride>,
     pub map: MapType,
+    pub embedded_resources: EmbeddedResources,
+    pub datetime: DateTimeBackend,
+    pub explicit_null: bool,
     pub relaxed: bool,
 }
 
+impl Default for Config {
+    #[allow(deprecated)]
+    fn default() -> Self {
+        Config {
+            no_condition: false,
+            no_object_reference: false,
+            known_types: KnownTypes::default(),
+            // string formats are opt-in
+            format_types: FormatTypes::empty(),
+            type_overrides: vec![],
+            map: MapType::default(),
+            embedded_resources: EmbeddedResources::default(),
+            datetime: DateTimeBackend::default(),
+            explicit_null: false,
+            relaxed: false,
+        }
+    }
+}
+
 /// Scan a schema for structs and members, and recurse to find all structs
 ///
 /// All found output structs will have its names prefixed by the kind it is for
-pub fn analyze(schema: JSONSchemaProps, kind: &str, cfg: Config) -> anyhow::Result<Output> {
-    let mut res = Output::default();
-    analyze_(&schema, "", kind, 0, &mut res, &cfg)?;
+pub fn analyze(schema: JSONSchemaProps, kind: &str, mut cfg: Config) -> anyhow::Result<Output> {
+    // the deprecated flags turn off the known types they were for
+    #[allow(deprecated)]
+    let disabled = [
+        (cfg.no_condition, "Condition"),
+        (cfg.no_object_reference, "ObjectReference"),
+    ];
+    for (disabled, name) in disabled {
+        if disabled {
+            cfg.known_types.remove(name);
+        }
+    }
+
+    let prefix = kind.to_upper_camel_case();
+    let schema = merge_all_of(schema, kind)?;
+
+    // overrides are looked up by the names of the types they replace
+    let overrides = std::mem::take(&mut cfg.type_overrides);
+    for type_override in &overrides {
+        for name in type_override.generated_names(kind) {
+            if name == format!("{}Spec", prefix) || name == format!("{}Status", prefix) {
+                anyhow::bail!(
+                    "cannot override the type of the spec or status with {}",
+                    type_override.target
+                );
+            }
+            cfg.type_overrides
+                .push(TypeOverride::new(name, &type_override.path));
+        }
+    }
+
+    // known types named like generated types would clash with them, so those are generated instead
+    let mut res = loop {
+        let mut res = Output::default();
+        analyze_(&schema, "", kind, 0, &mut res, &cfg)?;
+        let known = cfg.known_types.iter().count();
+        cfg.known_types.retain(|known| {
+            !res.containers()
+                .iter()
+                .any(|c| c.name == known.name() || rust_name(&prefix, &c.name) == known.name())
+        });
+        if cfg.known_types.iter().count() == known {
+            break res;
+        }
+    };
+
+    for type_override in &overrides {
+        // generic types are used as given, and plain types as a whole identifier
+        let type_name = type_override.type_name();
+        let is_used = |c: &Container| {
+            if type_name.contains('<') {
+                c.members.iter().any(|m| m.type_.contains(type_name))
+            } else {
+                c.uses_type(type_name)
+            }
+        };
+        if !res.containers().iter().any(is_used) {
+            res.warn(format!(
+                "type override for {} did not match any property",
+                type_override.target
+            ));
+        }
+    }
     Ok(res)
 }
 
@@ -47,6 +138,31 @@ fn analyze_(
 
     let camel_cased_stack = &stack.to_upper_camel_case();
 
+    // oneOf / anyOf with typed alternatives become data-carrying enums
+    if let Some(alternatives) = typed_alternatives(schema) {
+        log::debug!("Generating enum for {} (under {})", current, camel_cased_stack);
+        return analyze_alternatives(alternatives, schema, camel_cased_stack, level, results, cfg);
+    }
+
+    // scalars listing their values become plain enums, wherever they are found
+    if schema.enum_.is_some()
+        && matches!(
+            schema.type_.as_deref(),
+            Some("string" | "integer" | "number" | "boolean")
+        )
+    {
+        if is_generated_enum(schema) {
+            log::debug!("Generating enum for {} (under {})", current, camel_cased_stack);
+            results.insert(analyze_enum_properties(schema, camel_cased_stack, level)?);
+        } else {
+            results.warn(format!(
+                "not generating enum {} - only strings and integers can be variants",
+                camel_cased_stack
+            ));
+        }
+        return Ok(());
+    }
+
     // create a Container if we have a container type:
     //trace!("analyze_ with {} + {}", current, stack);
     if schema.type_.clone().unwrap_or_default() == "object" {
@@ -62,7 +178,7 @@ fn analyze_(
                     current,
                     camel_cased_stack
                 );
-                let c = extract_container(
+                let mut c = extract_container(
                     extra_props,
                     camel_cased_stack,
                     &mut array_recurse_level,
@@ -70,6 +186,8 @@ fn analyze_(
                     schema,
                     cfg,
                 )?;
+                // rules of the map apply to the map itself, not to its values
+                c.rules = s.x_kubernetes_validations.clone().unwrap_or_default();
                 results.insert(c); // deduplicated insert
             } else if dict_type == "object" {
                 // An empty object value (no properties / no nested additionalProperties) is a
@@ -85,8 +203,15 @@ fn analyze_(
                     );
                     analyze_(s, current, camel_cased_stack, level, results, cfg)?;
                 }
+            } else if is_generated_enum(s) {
+                // map values are an enum, named after the map
+                analyze_(s, current, camel_cased_stack, level, results, cfg)?;
+                return Ok(());
             } else if !dict_type.is_empty() {
-                log::warn!("not generating type {} - using {} map", current, dict_type);
+                results.warn(format!(
+                    "not generating type {} - using {} map",
+                    current, dict_type
+                ));
                 return Ok(()); // no members here - it'll be inlined
             }
         } else {
@@ -94,7 +219,7 @@ fn analyze_(
             log::debug!("Generating struct for {} (under {})", current, camel_cased_stack);
             // initial analysis of properties (we do not recurse here, we need to find members first)
             if props.is_empty() && schema.x_kubernetes_preserve_unknown_fields.unwrap_or(false) {
-                log::warn!("not generating type {} - using map", current);
+                results.warn(format!("not generating type {} - using map", current));
                 return Ok(());
             }
             let c = extract_container(
@@ -162,11 +287,23 @@ fn find_containers(
             log::debug!("not recursing into ignored {}", key); // handled elsewhere
             continue;
         }
+        if key == "metadata" && schema.x_kubernetes_embedded_resource == Some(true) {
+            log::debug!("not recursing into metadata of embedded resource {}", stack);
+            continue;
+        }
         let next_key = key.to_upper_camel_case();
         let next_stack = format!("{}{}", stack, next_key);
+        if type_override(&next_stack, cfg).is_some() {
+            log::debug!("not recursing into overridden {}", key);
+            continue;
+        }
         let value_type = value.type_.clone().unwrap_or_default();
         match value_type.as_ref() {
             "object" => {
+                if !is_root_container(key, level) && is_known(value, key, cfg) {
+                    log::debug!("not recursing into known type {}", key);
+                    continue;
+                }
                 // objects, maps
                 let mut handled_inner = false;
                 if let Some(JSONSchemaPropsOrBool::Schema(s)) = &value.additional_properties {
@@ -197,35 +334,40 @@ fn find_containers(
                     let mut inner = value.clone();
                     for _i in 0..recurse {
                         log::debug!("..recursing into props for {}", key);
-                        if let Some(sub) = inner.items {
+                        if let Some(sub) = &inner.items {
                             match sub {
                                 JSONSchemaPropsOrArray::Schema(s) => {
                                     //info!("got inner: {}", serde_json::to_string_pretty(&s)?);
                                     inner = *s.clone();
                                 }
-                                _ => anyhow::bail!("only handling single type in arrays"),
+                                JSONSchemaPropsOrArray::Schemas(_) => break,
                             }
                         } else {
                             anyhow::bail!("could not recurse into vec");
                         }
                     }
-                    analyze_(&inner, &next_key, &next_stack, level + 1, &mut results, cfg)?;
+                    if let Some(JSONSchemaPropsOrArray::Schemas(schemas)) = &inner.items {
+                        analyze_tuple(schemas, key, &next_key, &next_stack, level + 1, &mut results, cfg)?;
+                    } else {
+                        analyze_(&inner, &next_key, &next_stack, level + 1, &mut results, cfg)?;
+                    }
                 }
             }
             "" => {
                 if value.x_kubernetes_int_or_string.is_some() {
                     log::debug!("..not recursing into IntOrString {}", key)
+                } else if typed_alternatives(value).is_some() {
+                    log::debug!("..recursing into alternatives for {}", key);
+                    analyze_(value, &next_key, &next_stack, level + 1, &mut results, cfg)?;
                 } else {
                     log::debug!("..not recursing into unknown empty type {}", key)
                 }
             }
             x => {
-                if let Some(en) = &value.enum_ {
-                    // plain enums do not need to recurse, can collect it here
-                    // ....although this makes it impossible for us to handle enums at the top level
-                    // TODO: move this to the top level
-                    let new_result = analyze_enum_properties(en, &next_stack, level, schema)?;
-                    results.insert(new_result); // deduplicated insert
+                if value.enum_.is_some() {
+                    // plain enums are kept at the level of their parent
+                    log::debug!("..recursing into enum {}", key);
+                    analyze_(value, &next_key, &next_stack, level, &mut results, cfg)?;
                 } else {
                     log::debug!("..not recursing into {} ('{}' is not a container)", key, x)
                 }
@@ -235,27 +377,62 @@ fn find_containers(
     Ok(results)
 }
 
-// helper to figure out what output enums and embedded members are contained in the current object schema
-fn analyze_enum_properties(
-    items: &Vec<JSON>,
+// helper to recurse into the positions of a tuple, whose types are named after their position
+fn analyze_tuple(
+    schemas: &[JSONSchemaProps],
+    key: &str,
+    current: &str,
     stack: &str,
     level: u8,
-    schema: &JSONSchemaProps,
-) -> anyhow::Result<Container> {
+    results: &mut Output,
+    cfg: &Config,
+) -> anyhow::Result<()> {
+    for (i, s) in schemas.iter().enumerate() {
+        let (item_key, item_current, item_stack) = (
+            format!("{key}{i}"),
+            format!("{current}{i}"),
+            format!("{stack}{i}"),
+        );
+        if type_override(&item_stack, cfg).is_some() || is_known(s, &item_key, cfg) {
+            log::debug!("not recursing into known or overridden {}", item_key);
+            continue;
+        }
+        // positions may themselves be arrays of types
+        let mut inner = s;
+        while let Some(JSONSchemaPropsOrArray::Schema(items)) = &inner.items {
+            inner = items;
+        }
+        if let Some(JSONSchemaPropsOrArray::Schemas(schemas)) = &inner.items {
+            analyze_tuple(
+                schemas,
+                &item_key,
+                &item_current,
+                &item_stack,
+                level,
+                results,
+                cfg,
+            )?;
+        } else {
+            analyze_(inner, &item_current, &item_stack, level, results, cfg)?;
+        }
+    }
+    Ok(())
+}
+
+// helper to turn a scalar schema listing its values into a plain enum
+//
+// string values become variants named after them, while integer values become the discriminants of the variants
+fn analyze_enum_properties(schema: &JSONSchemaProps, stack: &str, level: u8) -> anyhow::Result<Container> {
     let mut members = vec![];
     log::debug!("analyzing enum {}", serde_json::to_string(&schema)?);
 
-    for en in items {
+    for en in schema.enum_.iter().flatten() {
         log::debug!("got enum {:?}", en);
-        // TODO: do we need to verify enum elements? only in oneOf only right?
         let name = match &en.0 {
             serde_json::Value::String(name) => name.to_string(),
-            serde_json::Value::Number(val) => {
-                if !val.is_u64() {
-                    anyhow::bail!("enum member cannot have signed/floating discriminants");
-                }
-                val.to_string()
-            }
+            serde_json::Value::Number(val) if !val.is_f64() => val.to_string(),
+            // nullable enums list null, which is the None of an optional member
+            serde_json::Value::Null => continue,
             _ => anyhow::bail!("not handling non-string/int enum outside oneOf block"),
         };
         let rust_type = "".to_string();
@@ -268,18 +445,167 @@ fn analyze_enum_properties(
             serde_annot: vec![],
             extra_annot: vec![],
             docs: member_doc,
+            default: None,
+            constraints: Default::default(),
+            rules: vec![],
+            list_type: None,
         })
     }
+    // the default is only kept when it names one of the variants
+    let default_variant = schema
+        .default
+        .as_ref()
+        .map(|JSON(value)| match value {
+            serde_json::Value::String(name) => name.to_string(),
+            other => other.to_string(),
+        })
+        .filter(|name| members.iter().any(|m| &m.name == name));
+    // integer enums are serialized as their discriminants
+    let repr = match schema.type_.as_deref() {
+        Some("integer") => Some(extract_enum_integer_type(schema)?),
+        _ => None,
+    };
     Ok(Container {
         name: stack.to_string(),
         members,
         level,
         docs: schema.description.clone(),
+        title: schema.title.clone(),
         is_enum: true,
+        default_variant,
+        repr,
+        rules: schema.x_kubernetes_validations.clone().unwrap_or_default(),
         ..Container::default()
     })
 }
 
+// helper to turn typed oneOf / anyOf alternatives into an enum with one data-carrying variant each
+//
+// if every alternative is an object pinning a shared property to a distinct constant,
+// that property is used as an internal tag, otherwise the enum is untagged.
+// untagged variants are tried in order, so objects are tried last, and those requiring more properties first
+fn analyze_alternatives(
+    alternatives: &[JSONSchemaProps],
+    schema: &JSONSchemaProps,
+    stack: &str,
+    level: u8,
+    results: &mut Output,
+    cfg: &Config,
+) -> anyhow::Result<()> {
+    let discriminator = find_discriminator(alternatives);
+    let mut members: Vec<Member> = vec![];
+    let mut nested = Output::default();
+
+    let mut alternatives = alternatives.iter().enumerate().collect::<Vec<_>>();
+    if discriminator.is_none() {
+        // derived structs also deserialize from sequences, so objects go last, requiring the most properties first
+        alternatives.sort_by_key(|(_, alt)| match alt.type_.as_deref() {
+            Some("object") => Some(std::cmp::Reverse(required_properties(alt))),
+            _ => None,
+        });
+    }
+    let mut catch_all: Option<String> = None;
+
+    for (i, alternative) in alternatives.iter().copied() {
+        let mut alternative = alternative.clone();
+        let mut name = if let Some((tag, values)) = &discriminator {
+            // the tag is consumed by serde, so it must not be a member of the variant struct
+            if let Some(props) = alternative.properties.as_mut() {
+                props.remove(tag);
+            }
+            if let Some(required) = alternative.required.as_mut() {
+                required.retain(|r| r != tag);
+            }
+            values[i].clone()
+        } else {
+            untagged_variant_name(&alternative, alternatives.iter().map(|(_, alt)| *alt))
+        };
+        // variant types are named after the variant, so they need to be unique before renaming
+        let base = name.clone();
+        let mut suffix = 1;
+        while members.iter().any(|m| m.name == name) {
+            suffix += 1;
+            name = format!("{}{}", base, suffix);
+        }
+        let variant = name.to_upper_camel_case();
+        if discriminator.is_none() && alternative.type_.as_deref() == Some("object") {
+            // an object requiring nothing deserializes from any object, leaving later ones unreachable
+            if let Some(earlier) = &catch_all {
+                results.warn(format!(
+                    "variant {}::{} can never be deserialized - {} before it accepts any object",
+                    stack, variant, earlier
+                ));
+            } else if required_properties(&alternative) == 0 {
+                catch_all = Some(variant.clone());
+            }
+        }
+        let next_stack = format!("{}{}", stack, variant);
+
+        let rust_type = match alternative.type_.clone().unwrap_or_default().as_ref() {
+            "object" => {
+                analyze_(&alternative, &variant, &next_stack, level + 1, &mut nested, cfg)?;
+                extract_object_type(&alternative, stack, &variant, cfg)?
+            }
+            "string" | "integer" if is_generated_enum(&alternative) => {
+                analyze_(&alternative, &variant, &next_stack, level + 1, &mut nested, cfg)?;
+                next_stack.clone()
+            }
+            "string" => string_type(&alternative, cfg),
+            "boolean" => "bool".to_string(),
+            "number" => extract_number_type(&alternative)?,
+            "integer" => extract_integer_type(&alternative)?,
+            "array" => {
+                // items are commonly declared once on the parent rather than on the alternative
+                if alternative.items.is_none() {
+                    alternative.items = schema.items.clone();
+                }
+                let (array_type, recurse_level) =
+                    array_recurse_for_type(&alternative, stack, &variant, 1, cfg)?;
+                let mut inner = alternative.clone();
+                for _i in 0..recurse_level {
+                    match inner.items {
+                        Some(JSONSchemaPropsOrArray::Schema(s)) => inner = *s,
+                        _ => anyhow::bail!("only handling single type in arrays"),
+                    }
+                }
+                analyze_(&inner, &variant, &next_stack, level + 1, &mut nested, cfg)?;
+                array_type
+            }
+            x => anyhow::bail!("unknown type {} in alternatives for {}", x, stack),
+        };
+        log::debug!("with variant {} of type {}", name, rust_type);
+        members.push(Member {
+            type_: rust_type,
+            name,
+            serde_annot: vec![],
+            extra_annot: vec![],
+            docs: alternative.description.clone(),
+            default: None,
+            constraints: extract_constraints(&alternative),
+            rules: alternative.x_kubernetes_validations.clone().unwrap_or_default(),
+            list_type: None,
+        });
+    }
+
+    let serde_annot = match discriminator {
+        Some((tag, _)) => vec![format!("tag = \"{}\"", tag)],
+        None => vec!["untagged".to_string()],
+    };
+    results.insert(Container {
+        name: stack.to_string(),
+        members,
+        level,
+        docs: schema.description.clone(),
+        title: schema.title.clone(),
+        is_enum: true,
+        serde_annot,
+        rules: schema.x_kubernetes_validations.clone().unwrap_or_default(),
+        ..Container::default()
+    });
+    results.extend(nested);
+    Ok(())
+}
+
 // fully populate a Container with all its members given the current stack and schema position
 fn extract_container(
     props: &BTreeMap<String, JSONSchemaProps>,
@@ -295,43 +621,43 @@ fn extract_container(
     for (key, value) in props {
         let value_type = value.type_.clone().unwrap_or_default();
         let rust_type = match value_type.as_ref() {
+            "object" if key == "metadata" && schema.x_kubernetes_embedded_resource == Some(true) => {
+                "ObjectMeta".to_string()
+            }
             "object" => extract_object_type(value, stack, key, cfg)?,
-            "string" => {
-                if let Some(_en) = &value.enum_ {
-                    log::trace!("got enum string: {}", serde_json::to_string(&schema)?);
-                    format!("{}{}", stack, key.to_upper_camel_case())
-                } else {
-                    "String".to_string()
-                }
+            "string" | "integer" if is_generated_enum(value) => {
+                log::trace!("got enum {}: {}", value_type, serde_json::to_string(&schema)?);
+                generated_type(stack, key, cfg)
+            }
+            "string" | "boolean" | "date" | "number" | "integer" if is_overridden(stack, key, cfg) => {
+                generated_type(stack, key, cfg)
             }
+            "string" => string_type(value, cfg),
             "boolean" => "bool".to_string(),
-            "date" => extract_date_type(value)?,
+            "date" => extract_date_type(value, cfg)?,
             "number" => extract_number_type(value)?,
             "integer" => extract_integer_type(value)?,
             "array" => {
                 // recurse through repeated arrays until we find a concrete type (keep track of how deep we went)
-                let (mut array_type, recurse_level) = array_recurse_for_type(value, stack, key, 1, cfg)?;
+                let (array_type, recurse_level) = array_recurse_for_type(value, stack, key, 1, cfg)?;
                 log::trace!("got array {} for {} in level {}", array_type, key, recurse_level);
-                if !cfg.no_condition && key == "conditions" && is_conditions(value) {
-                    array_type = "Vec<Condition>".into();
-                } else if !cfg.no_object_reference && is_object_ref_list(value) {
-                    array_type = "Vec<ObjectReference>".into()
-                } else {
+                if !is_known(value, key, cfg) {
                     array_recurse_level.insert(key.clone(), recurse_level);
                 }
                 array_type
             }
             "" => {
                 let map_type = cfg.map.name();
-                if value.x_kubernetes_int_or_string.is_some() {
+                if is_overridden(stack, key, cfg) {
+                    generated_type(stack, key, cfg)
+                } else if let Some(format_type) = cfg.format_types.find(value) {
+                    format_type.type_name().to_string()
+                } else if value.x_kubernetes_int_or_string.is_some() {
                     "IntOrString".into()
-                } else if value.x_kubernetes_preserve_unknown_fields == Some(true)
-                    || value
-                        .one_of
-                        .as_deref()
-                        .is_some_and(|items| items.iter().all(|item| item.type_.is_some()))
-                {
+                } else if value.x_kubernetes_preserve_unknown_fields == Some(true) {
                     "serde_json::Value".into()
+                } else if typed_alternatives(value).is_some() {
+                    generated_type(stack, key, cfg)
                 } else if cfg.relaxed {
                     log::debug!("found empty object at {} key: {}", stack, key);
                     format!("{map_type}<String, serde_json::Value>")
@@ -344,44 +670,89 @@ fn extract_container(
 
         // Create member and wrap types correctly
         let member_doc = value.description.clone();
-        if reqs.contains(key) {
+        let member_default = match &value.default {
+            Some(JSON(default)) if !default.is_null() => Some(default.clone()),
+            _ => None,
+        };
+        let nullable = value.nullable == Some(true);
+        let (type_, serde_annot): (_, Vec<String>) = if reqs.contains(key) {
             log::debug!("with required member {} of type {}", key, &rust_type);
-            members.push(Member {
-                type_: rust_type,
-                name: key.to_string(),
-                serde_annot: vec![],
-                extra_annot: vec![],
-                docs: member_doc,
-            })
+            if nullable {
+                // a required but nullable member is always serialized, as null when unset
+                (format!("Option<{}>", rust_type), vec![])
+            } else {
+                (rust_type, vec![])
+            }
+        } else if nullable && cfg.explicit_null {
+            // absent is None, while an explicit null is Some(None), so merge patches can unset the member
+            log::debug!("with optional nullable member {} of type {}", key, rust_type);
+            (format!("Option<Option<{}>>", rust_type), vec![
+                "default".into(),
+                "skip_serializing_if = \"Option::is_none\"".into(),
+                "deserialize_with = \"deserialize_some\"".into(),
+            ])
         } else {
             // option wrapping needed if not required
             log::debug!("with optional member {} of type {}", key, rust_type);
-            members.push(Member {
-                type_: format!("Option<{}>", rust_type),
-                name: key.to_string(),
-                serde_annot: vec![
-                    "default".into(),
-                    "skip_serializing_if = \"Option::is_none\"".into(),
-                ],
-                extra_annot: vec![],
-                docs: member_doc,
-            })
-            // TODO: must capture `default` key here instead of blindly using serde default
-            // this will require us storing default properties for the member in above loop
-            // This is complicated because serde default requires a default fn / impl Default
-            // probably better to do impl Default to avoid having to make custom fns
-        }
+            (format!("Option<{}>", rust_type), vec![
+                "default".into(),
+                "skip_serializing_if = \"Option::is_none\"".into(),
+            ])
+        };
+        members.push(Member {
+            type_,
+            name: key.to_string(),
+            serde_annot,
+            extra_annot: vec![],
+            docs: member_doc,
+            default: member_default,
+            constraints: extract_constraints(value),
+            rules: value.x_kubernetes_validations.clone().unwrap_or_default(),
+            list_type: extract_list_type(value),
+        })
     }
     Ok(Container {
         name: stack.to_string(),
         members,
         level,
         docs: schema.description.clone(),
+        title: schema.title.clone(),
         is_enum: false,
+        rules: schema.x_kubernetes_validations.clone().unwrap_or_default(),
         ..Container::default()
     })
 }
 
+// value constraints of a property, validated by the generated code when requested
+fn extract_constraints(value: &JSONSchemaProps) -> Constraints {
+    Constraints {
+        minimum: value.minimum,
+        maximum: value.maximum,
+        exclusive_minimum: value.exclusive_minimum.unwrap_or_default(),
+        exclusive_maximum: value.exclusive_maximum.unwrap_or_default(),
+        multiple_of: value.multiple_of,
+        pattern: value.pattern.clone(),
+        min_length: value.min_length,
+        max_length: value.max_length,
+        min_items: value.min_items,
+        max_items: value.max_items,
+        unique_items: value.unique_items.unwrap_or_default(),
+        min_properties: value.min_properties,
+        max_properties: value.max_properties,
+    }
+}
+
+// how the items of an array property are merged, when they are not replaced as a whole
+fn extract_list_type(value: &JSONSchemaProps) -> Option<ListType> {
+    match value.x_kubernetes_list_type.as_deref()? {
+        "set" => Some(ListType::Set),
+        "map" => Some(ListType::Map(
+            value.x_kubernetes_list_map_keys.clone().unwrap_or_default(),
+        )),
+        _ => None,
+    }
+}
+
 fn resolve_additional_properties(
     additional: &JSONSchemaPropsOrBool,
     stack: &str,
@@ -397,7 +768,8 @@ fn resolve_additional_properties(
     let dict_type = s.type_.clone().unwrap_or_default();
     log::debug!("dict type is {dict_type}");
     let dict_key = match dict_type.as_ref() {
-        "string" => Some("String".into()),
+        "string" | "integer" if is_generated_enum(s) => Some(generated_type(stack, key, cfg)),
+        "string" => Some(string_type(s, cfg)),
         // We are not 100% sure the array and object subcases here are correct, but they pass tests atm.
         // Authoritative, but more detailed sources than the CRD validation docs below are welcome.
         // https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#validation
@@ -413,7 +785,9 @@ fn resolve_additional_properties(
             }
         }
         "" => {
-            if s.x_kubernetes_int_or_string.is_some() {
+            if let Some(format_type) = cfg.format_types.find(s) {
+                Some(format_type.type_name().to_string())
+            } else if s.x_kubernetes_int_or_string.is_some() {
                 Some("IntOrString".into())
             } else if s.x_kubernetes_preserve_unknown_fields == Some(true) {
                 Some("serde_json::Value".into())
@@ -440,104 +814,367 @@ fn array_recurse_for_type(
     level: u8,
     cfg: &Config,
 ) -> anyhow::Result<(String, u8)> {
-    if let Some(items) = &value.items {
-        match items {
-            JSONSchemaPropsOrArray::Schema(s) => {
-                if s.type_.is_none() && s.x_kubernetes_preserve_unknown_fields == Some(true) {
-                    return Ok(("Vec<serde_json::Value>".to_string(), level));
+    match &value.items {
+        Some(JSONSchemaPropsOrArray::Schema(s))
+            if s.type_.as_deref() == Some("array") && s.items.is_none() && cfg.relaxed =>
+        {
+            log::warn!("Empty inner array in: {} key: {}", stack, key);
+            let map_type = cfg.map.name();
+            Ok((format!("{}<String, serde_json::Value>", map_type), level))
+        }
+        Some(JSONSchemaPropsOrArray::Schema(s)) => {
+            let (item_type, recurse_level) = array_item_type(s, stack, key, level, cfg)?;
+            Ok((format!("Vec<{}>", item_type), recurse_level))
+        }
+        // a schema per position is a tuple, whose generated types are named after their position
+        Some(JSONSchemaPropsOrArray::Schemas(schemas)) => {
+            if schemas.is_empty() {
+                anyhow::bail!("empty tuple in array type for {}", key);
+            }
+            let item_types = schemas
+                .iter()
+                .enumerate()
+                .map(|(i, s)| Ok(array_item_type(s, stack, &format!("{key}{i}"), level, cfg)?.0))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let tuple_type = match item_types.as_slice() {
+                [single] => format!("({single},)"),
+                _ => format!("({})", item_types.join(", ")),
+            };
+            Ok((tuple_type, level))
+        }
+        None => anyhow::bail!("missing items in array type"),
+    }
+}
+
+// the type of the items of an array, along with the array nesting level of the items
+fn array_item_type(
+    s: &JSONSchemaProps,
+    stack: &str,
+    key: &str,
+    level: u8,
+    cfg: &Config,
+) -> anyhow::Result<(String, u8)> {
+    if s.type_.is_none() && s.x_kubernetes_preserve_unknown_fields == Some(true) {
+        return Ok(("serde_json::Value".to_string(), level));
+    }
+    let inner_array_type = s.type_.clone().unwrap_or_default();
+    match inner_array_type.as_ref() {
+        "object" => Ok((extract_object_type(s, stack, key, cfg)?, level)),
+        "string" | "integer" if is_generated_enum(s) => Ok((generated_type(stack, key, cfg), level)),
+        "string" | "boolean" | "date" | "number" | "integer" if is_overridden(stack, key, cfg) => {
+            Ok((generated_type(stack, key, cfg), level))
+        }
+        "string" => Ok((string_type(s, cfg), level)),
+        "boolean" => Ok(("bool".into(), level)),
+        "date" => Ok((extract_date_type(s, cfg)?, level)),
+        "number" => Ok((extract_number_type(s)?, level)),
+        "integer" => Ok((extract_integer_type(s)?, level)),
+        "array" => {
+            if s.items.is_some() {
+                array_recurse_for_type(s, stack, key, level + 1, cfg)
+            } else {
+                anyhow::bail!("Empty inner array in: {} key: {}", stack, key);
+            }
+        }
+        "" => {
+            if is_overridden(stack, key, cfg) {
+                Ok((generated_type(stack, key, cfg), level))
+            } else if let Some(format_type) = cfg.format_types.find(s) {
+                Ok((format_type.type_name().to_string(), level))
+            } else if s.x_kubernetes_int_or_string.is_some() {
+                Ok(("IntOrString".into(), level))
+            } else if typed_alternatives(s).is_some() {
+                Ok((generated_type(stack, key, cfg), level))
+            } else {
+                anyhow::bail!("unknown empty array type for {}", key)
+            }
+        }
+        unknown => {
+            anyhow::bail!("unsupported recursive array type \"{unknown}\" for {key}")
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// helpers
+/// The alternatives of a `oneOf` / `anyOf` schema, if each of them declares a type
+///
+/// Schemas that already map to a known type (`IntOrString`, free-form values) are excluded.
+fn typed_alternatives(value: &JSONSchemaProps) -> Option<&Vec<JSONSchemaProps>> {
+    if value.type_.is_some()
+        || value.properties.is_some()
+        || value.x_kubernetes_int_or_string.is_some()
+        || value.x_kubernetes_preserve_unknown_fields == Some(true)
+    {
+        return None;
+    }
+    let alternatives = value.one_of.as_ref().or(value.any_of.as_ref())?;
+    if !alternatives.is_empty() && alternatives.iter().all(|alt| alt.type_.is_some()) {
+        Some(alternatives)
+    } else {
+        None
+    }
+}
+
+/// The name of an untagged variant
+///
+/// This is the title of the alternative, a type name starting its description (e.g. `BearerToken is ..`),
+/// a required property that no other alternative requires, or otherwise its type.
+fn untagged_variant_name<'a>(
+    alternative: &JSONSchemaProps,
+    alternatives: impl Iterator<Item = &'a JSONSchemaProps>,
+) -> String {
+    if let Some(title) = alternative.title.as_deref().filter(|t| !t.trim().is_empty()) {
+        return title.to_upper_camel_case();
+    }
+    if let Some(desc) = &alternative.description {
+        let mut words = desc.split_whitespace();
+        if let (Some(first), Some("is" | "represents" | "defines" | "specifies" | "configures")) =
+            (words.next(), words.next())
+        {
+            if is_type_name(first) {
+                return first.to_string();
+            }
+        }
+    }
+    let others = alternatives
+        .filter(|alt| *alt != alternative)
+        .flat_map(|alt| alt.required.iter().flatten())
+        .collect::<Vec<_>>();
+    if let Some(required) = alternative
+        .required
+        .iter()
+        .flatten()
+        .find(|r| !others.contains(r))
+    {
+        return required.to_upper_camel_case();
+    }
+    alternative
+        .type_
+        .clone()
+        .unwrap_or_default()
+        .to_upper_camel_case()
+}
+
+/// The number of properties an object alternative requires
+///
+/// Maps and objects requiring nothing deserialize from any object.
+fn required_properties(alternative: &JSONSchemaProps) -> usize {
+    match &alternative.properties {
+        Some(props) if !props.is_empty() => alternative.required.as_ref().map_or(0, Vec::len),
+        _ => 0,
+    }
+}
+
+/// Find a property that all object alternatives pin to a distinct constant string
+///
+/// Returns the property name along with the constant for each alternative.
+fn find_discriminator(alternatives: &[JSONSchemaProps]) -> Option<(String, Vec<String>)> {
+    let first = alternatives.first()?.properties.as_ref()?;
+    'keys: for key in first.keys() {
+        let mut values: Vec<String> = vec![];
+        for alt in alternatives {
+            let constant = alt
+                .properties
+                .as_ref()
+                .and_then(|p| p.get(key))
+                .and_then(|p| p.enum_.as_ref())
+                .filter(|en| en.len() == 1)
+                .and_then(|en| en[0].0.as_str());
+            match constant {
+                Some(c) if !values.iter().any(|v| v == c) => values.push(c.to_string()),
+                _ => continue 'keys,
+            }
+        }
+        return Some((key.clone(), values));
+    }
+    None
+}
+
+/// Merge the branches of every `allOf` into the schema declaring them
+///
+/// Properties declared by several branches are merged in turn, and requirements and rules are combined.
+/// Branches giving any other keyword different values fail, except for documentation where the first one wins.
+fn merge_all_of(schema: JSONSchemaProps, kind: &str) -> anyhow::Result<JSONSchemaProps> {
+    let mut value = serde_json::to_value(schema)?;
+    merge_all_of_value(&mut value, kind)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+fn merge_all_of_value(value: &mut serde_json::Value, path: &str) -> anyhow::Result<()> {
+    let Some(schema) = value.as_object_mut() else {
+        return Ok(());
+    };
+    if let Some(branches) = schema.remove("allOf") {
+        for mut branch in branches.as_array().cloned().unwrap_or_default() {
+            merge_all_of_value(&mut branch, path)?;
+            if let serde_json::Value::Object(branch) = branch {
+                merge_schema(schema, branch, path)?;
+            }
+        }
+    }
+
+    if let Some(serde_json::Value::Object(props)) = schema.get_mut("properties") {
+        for (key, prop) in props.iter_mut() {
+            merge_all_of_value(prop, &format!("{path}.{key}"))?;
+        }
+    }
+    for key in ["items", "additionalProperties"] {
+        match schema.get_mut(key) {
+            Some(serde_json::Value::Array(items)) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    merge_all_of_value(item, &format!("{path}[{i}]"))?;
                 }
-                let inner_array_type = s.type_.clone().unwrap_or_default();
-                match inner_array_type.as_ref() {
-                    "object" => {
-                        let vec_value = extract_object_type(s, stack, key, cfg)?;
+            }
+            Some(inner) => merge_all_of_value(inner, &format!("{path}[]"))?,
+            None => {}
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(serde_json::Value::Array(alternatives)) = schema.get_mut(key) {
+            for alternative in alternatives {
+                merge_all_of_value(alternative, path)?;
+            }
+        }
+    }
+    Ok(())
+}
 
-                        Ok((format!("Vec<{}>", vec_value), level))
-                    }
-                    "string" => Ok(("Vec<String>".into(), level)),
-                    "boolean" => Ok(("Vec<bool>".into(), level)),
-                    "date" => Ok((format!("Vec<{}>", extract_date_type(value)?), level)),
-                    "number" => Ok((format!("Vec<{}>", extract_number_type(value)?), level)),
-                    "integer" => Ok((format!("Vec<{}>", extract_integer_type(value)?), level)),
-                    "array" => {
-                        if s.items.is_some() {
-                            let (array_type, recurse_level) =
-                                array_recurse_for_type(s, stack, key, level + 1, cfg)?;
-
-                            Ok((format!("Vec<{}>", array_type), recurse_level))
-                        } else if cfg.relaxed {
-                            log::warn!("Empty inner array in: {} key: {}", stack, key);
-                            let map_type = cfg.map.name();
-                            Ok((format!("{}<String, serde_json::Value>", map_type), level))
-                        } else {
-                            anyhow::bail!("Empty inner array in: {} key: {}", stack, key);
+// helper to merge one (already merged) allOf branch into a schema
+fn merge_schema(
+    schema: &mut serde_json::Map<String, serde_json::Value>,
+    branch: serde_json::Map<String, serde_json::Value>,
+    path: &str,
+) -> anyhow::Result<()> {
+    use serde_json::Value;
+    for (key, value) in branch {
+        let Some(existing) = schema.get_mut(&key) else {
+            schema.insert(key, value);
+            continue;
+        };
+        match (key.as_str(), existing, value) {
+            ("type", Value::String(a), Value::String(b)) if *a != b => {
+                anyhow::bail!("conflicting types for {} in allOf: {} and {}", path, a, b)
+            }
+            ("properties", Value::Object(props), Value::Object(branch_props)) => {
+                for (name, prop) in branch_props {
+                    match (props.get_mut(&name), prop) {
+                        (Some(Value::Object(existing)), Value::Object(prop)) => {
+                            merge_schema(existing, prop, &format!("{path}.{name}"))?
                         }
-                    }
-                    "" => {
-                        if s.x_kubernetes_int_or_string.is_some() {
-                            Ok(("Vec<IntOrString>".into(), level))
-                        } else {
-                            anyhow::bail!("unknown empty array type for {}", key)
+                        (Some(_), _) => {}
+                        (None, prop) => {
+                            props.insert(name, prop);
                         }
                     }
-                    unknown => {
-                        anyhow::bail!("unsupported recursive array type \"{unknown}\" for {key}")
+                }
+            }
+            ("items" | "additionalProperties", Value::Object(inner), Value::Object(branch_inner)) => {
+                merge_schema(inner, branch_inner, &format!("{path}[]"))?
+            }
+            ("required", Value::Array(required), Value::Array(branch_required)) => {
+                for name in branch_required {
+                    if !required.contains(&name) {
+                        required.push(name);
                     }
                 }
             }
-            // maybe fallback to serde_json::Value
-            _ => anyhow::bail!("only support single schema in array {}", key),
+            ("x-kubernetes-validations", Value::Array(rules), Value::Array(branch_rules)) => {
+                rules.extend(branch_rules)
+            }
+            // documentation does not change the generated type
+            ("description" | "title" | "example" | "externalDocs", _, _) => {}
+            (key, existing, value) if *existing != value => {
+                anyhow::bail!(
+                    "conflicting {} for {} in allOf: {} and {}",
+                    key,
+                    path,
+                    existing,
+                    value
+                )
+            }
+            _ => {}
         }
-    } else {
-        anyhow::bail!("missing items in array type")
     }
+    Ok(())
 }
 
-// ----------------------------------------------------------------------------
-// helpers
-fn is_conditions(value: &JSONSchemaProps) -> bool {
-    if let Some(JSONSchemaPropsOrArray::Schema(props)) = &value.items {
-        if let Some(p) = &props.properties {
-            let type_ = p.get("type");
-            let status = p.get("status");
-            let reason = p.get("reason");
-            let message = p.get("message");
-            let ltt = p.get("lastTransitionTime");
-            if type_.is_some() && status.is_some() && reason.is_some() && message.is_some() && ltt.is_some() {
-                return true;
-            }
-        }
+/// The override of the type generated with this name, if any
+fn type_override<'a>(name: &str, cfg: &'a Config) -> Option<&'a TypeOverride> {
+    cfg.type_overrides
+        .iter()
+        .find(|type_override| type_override.target == name)
+}
+
+/// Whether the property `key` under `stack` has a type override
+///
+/// This replaces the type of scalar properties and items, as it replaces the types generated for objects.
+fn is_overridden(stack: &str, key: &str, cfg: &Config) -> bool {
+    type_override(&format!("{}{}", stack, key.to_upper_camel_case()), cfg).is_some()
+}
+
+/// The type of a string, which is `String` unless its format has a type
+fn string_type(value: &JSONSchemaProps, cfg: &Config) -> String {
+    if let Some(format_type) = cfg.format_types.find(value) {
+        return format_type.type_name().to_string();
+    }
+    let date_type = match value.format.as_deref() {
+        Some("date-time") => cfg.datetime.date_time(),
+        Some("date") => cfg.datetime.date(),
+        _ => None,
+    };
+    date_type.map_or("String", |(type_, _)| type_).to_string()
+}
+
+/// Whether a scalar schema listing its values becomes a generated enum
+///
+/// Only strings and integers can be variants. Other values (floats, booleans or a mix of types) keep the scalar type.
+fn is_generated_enum(value: &JSONSchemaProps) -> bool {
+    let Some(values) = &value.enum_ else {
+        return false;
+    };
+    let mut values = values
+        .iter()
+        .map(|JSON(value)| value)
+        .filter(|value| !value.is_null());
+    match value.type_.as_deref() {
+        Some("string") => values.all(serde_json::Value::is_string),
+        Some("integer") => values.all(|value| value.is_i64() || value.is_u64()),
+        _ => false,
     }
-    false
 }
 
-fn is_object_ref_list(value: &JSONSchemaProps) -> bool {
-    if let Some(JSONSchemaPropsOrArray::Schema(props)) = &value.items {
-        is_object_ref(props)
-    } else {
-        false
+/// The type generated for the property `key` under `stack`, or its override
+fn generated_type(stack: &str, key: &str, cfg: &Config) -> String {
+    let name = format!("{}{}", stack, key.to_upper_camel_case());
+    match type_override(&name, cfg) {
+        Some(type_override) => type_override.type_name().to_string(),
+        None => name,
     }
 }
 
-fn is_object_ref(value: &JSONSchemaProps) -> bool {
-    if let Some(p) = &value.properties {
-        if p.len() != 7 {
-            return false;
-        }
-        let api_version = p.get("apiVersion");
-        let field_path = p.get("fieldPath");
-        let kind = p.get("kind");
-        let name = p.get("name");
-        let ns = p.get("namespace");
-        let rv = p.get("resourceVersion");
-        let uid = p.get("uid");
-        if [api_version, field_path, kind, name, ns, rv, uid]
-            .iter()
-            .all(|k| k.is_some())
-        {
-            return true;
+/// Whether a property is the spec or status of the resource, which are generated whatever their shape
+fn is_root_container(key: &str, level: u8) -> bool {
+    level == 0 && (key == "spec" || key == "status")
+}
+
+/// Whether an object schema, the items of an array schema or the values of a map schema have a known type
+///
+/// Embedded resources typed as `DynamicObject` count as known.
+fn is_known(value: &JSONSchemaProps, key: &str, cfg: &Config) -> bool {
+    let mut inner = value;
+    loop {
+        match (&inner.items, &inner.additional_properties) {
+            (Some(JSONSchemaPropsOrArray::Schema(items)), _) => inner = items,
+            (_, Some(JSONSchemaPropsOrBool::Schema(values))) => inner = values,
+            _ => return is_dynamic_object(inner, cfg) || cfg.known_types.find(key, inner).is_some(),
         }
     }
-    false
+}
+
+/// Whether an object schema is an embedded resource typed as a `DynamicObject`
+fn is_dynamic_object(value: &JSONSchemaProps, cfg: &Config) -> bool {
+    value.x_kubernetes_embedded_resource == Some(true) && cfg.embedded_resources == EmbeddedResources::Dynamic
 }
 
 fn extract_object_type(
@@ -546,6 +1183,13 @@ fn extract_object_type(
     key: &str,
     cfg: &Config,
 ) -> anyhow::Result<String> {
+    if let Some(type_override) = type_override(&format!("{}{}", stack, key.to_upper_camel_case()), cfg) {
+        return Ok(type_override.type_name().to_string());
+    }
+    if is_dynamic_object(value, cfg) {
+        return Ok("DynamicObject".to_string());
+    }
+
     let mut dict_key = None;
     if let Some(additional) = &value.additional_properties {
         dict_key = resolve_additional_properties(additional, stack, key, cfg)?;
@@ -554,32 +1198,31 @@ fn extract_object_type(
     }
     Ok(if let Some(dict) = dict_key {
         format!("{}<String, {}>", cfg.map.name(), dict)
-    } else if !cfg.no_object_reference && is_object_ref(value) {
-        "ObjectReference".into()
+    } else if let Some(known) = cfg.known_types.find(key, value) {
+        known.name().to_string()
     } else {
         format!("{}{}", stack, key.to_upper_camel_case())
     })
 }
 
-fn extract_date_type(value: &JSONSchemaProps) -> anyhow::Result<String> {
+fn extract_date_type(value: &JSONSchemaProps, cfg: &Config) -> anyhow::Result<String> {
+    // the non-standard `date` type has always used chrono, unless another backend is chosen
+    let backend = cfg.datetime.or_chrono();
     Ok(if let Some(f) = &value.format {
-        // NB: these need chrono feature on serde
-        match f.as_ref() {
-            // Not sure if the first actually works properly...
-            // might need a Date<Utc> but chrono docs advocated for NaiveDate
-            "date" => "NaiveDate".to_string(),
-            "date-time" => "DateTime<Utc>".to_string(),
+        let date_type = match f.as_ref() {
+            "date" => backend.date(),
+            "date-time" => backend.date_time(),
             x => {
                 anyhow::bail!("unknown date {}", x);
             }
-        }
+        };
+        date_type.map_or("String", |(type_, _)| type_).to_string()
     } else {
         "String".to_string()
     })
 }
 
 fn extract_number_type(value: &JSONSchemaProps) -> anyhow::Result<String> {
-    // TODO: byte / password here?
     Ok(if let Some(f) = &value.format {
         match f.as_ref() {
             "float" => "f32".to_string(),
@@ -613,6 +1256,27 @@ fn extract_integer_type(value: &JSONSchemaProps) -> anyhow::Result<String> {
     })
 }
 
+/// The discriminant type of an integer enum
+///
+/// This is the integer type of the schema when all values fit it, and otherwise `i64`, or `u64` for values above `i64::MAX`.
+fn extract_enum_integer_type(value: &JSONSchemaProps) -> anyhow::Result<String> {
+    let values = value
+        .enum_
+        .iter()
+        .flatten()
+        .filter_map(|JSON(v)| v.as_i64().map(i128::from).or_else(|| v.as_u64().map(i128::from)))
+        .collect::<Vec<_>>();
+    let fits = |type_: &str| values.iter().all(|&v| integer_fits(type_, v));
+    let integer_type = extract_integer_type(value)?;
+    Ok(if fits(&integer_type) {
+        integer_type
+    } else if fits("i64") {
+        "i64".to_string()
+    } else {
+        "u64".to_string()
+    })
+}
+
 // unit tests particular schema patterns
 #[cfg(test)]
 mod test {
@@ -620,7 +1284,10 @@ mod test {
 
     use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::JSONSchemaProps;
 
-    use super::{analyze, Config as Cfg};
+    use super::{analyze, Config as Cfg, Constraints};
+    use crate::{
+        Container, DateTimeBackend, EmbeddedResources, FormatTypes, KnownTypes, ListType, TypeOverride,
+    };
 
     static START: Once = Once::new();
     fn init() {
@@ -975,21 +1642,368 @@ type: object
 
         let member = &root.members[0];
         assert_eq!(member.name, "ambassadorId");
-        assert_eq!(member.type_, "serde_json::Value");
+        assert_eq!(member.type_, "HostAmbassadorId");
 
         let member = &root.members[1];
         assert_eq!(member.name, "other");
-        assert_eq!(member.type_, "Option<serde_json::Value>");
+        assert_eq!(member.type_, "Option<HostOther>");
+
+        // alternatives become an untagged enum with data-carrying variants
+        let ambassador = &structs[1];
+        assert_eq!(ambassador.name, "HostAmbassadorId");
+        assert_eq!(ambassador.level, 1);
+        assert!(ambassador.is_enum);
+        assert_eq!(ambassador.serde_annot, vec!["untagged"]);
+        assert_eq!(&ambassador.members[0].name, "String");
+        assert_eq!(&ambassador.members[0].type_, "String");
+        assert_eq!(&ambassador.members[1].name, "Array");
+        assert_eq!(&ambassador.members[1].type_, "Vec<String>");
     }
 
     #[test]
-    fn boolean_in_additional_properties() {
-        // as found in argo-app
+    fn untagged_variant_names_and_order() {
         init();
         let schema_str = r#"
+            type: object
             properties:
-              options:
-                additionalProperties:
+              source:
+                oneOf:
+                  - type: object
+                    properties:
+                      path:
+                        type: string
+                  - type: object
+                    description: SecretSource is a secret holding the value.
+                    required: [name, key]
+                    properties:
+                      name:
+                        type: string
+                      key:
+                        type: string
+                  - type: string
+                  - type: object
+                    title: inline value
+                    required: [value]
+                    properties:
+                      value:
+                        type: string
+                  - type: object
+                    additionalProperties:
+                      type: string
+                  - type: object
+                    description: This is a remote value.
+                    required: [url]
+                    properties:
+                      url:
+                        type: string
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let output = analyze(schema, "Config", Cfg::default()).unwrap();
+
+        // a second object requiring nothing can never be reached
+        assert_eq!(output.warnings(), [
+            "variant ConfigSource::Object2 can never be deserialized - Object before it accepts any object",
+            "not generating type Object2 - using string map",
+        ]);
+
+        // variants are named after titles, type names in descriptions, required properties or their type,
+        // with objects tried last, requiring more properties first. `This is ..` does not name a type, so `Url` does
+        let structs = output.output();
+        let source = &structs[1];
+        assert_eq!(source.serde_annot, vec!["untagged"]);
+        let variants = source
+            .members
+            .iter()
+            .map(|m| (m.name.as_str(), m.type_.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(variants, [
+            ("String", "String"),
+            ("SecretSource", "ConfigSourceSecretSource"),
+            ("InlineValue", "ConfigSourceInlineValue"),
+            ("Url", "ConfigSourceUrl"),
+            ("Object", "ConfigSourceObject"),
+            ("Object2", "BTreeMap<String, String>"),
+        ]);
+    }
+
+    #[test]
+    fn any_of_objects_with_discriminator() {
+        init();
+        let schema_str = r#"
+            type: object
+            properties:
+              auth:
+                anyOf:
+                  - type: object
+                    required: [type, token]
+                    properties:
+                      type:
+                        type: string
+                        enum: [bearer]
+                      token:
+                        type: string
+                  - type: object
+                    required: [type]
+                    properties:
+                      type:
+                        type: string
+                        enum: [basic-auth]
+                      username:
+                        type: string
+                  - type: string
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Client", Cfg::default()).unwrap().output();
+
+        // a plain string alternative rules out a discriminator
+        let auth = &structs[1];
+        assert_eq!(auth.name, "ClientAuth");
+        assert_eq!(auth.serde_annot, vec!["untagged"]);
+        assert_eq!(&auth.members[0].name, "String");
+        assert_eq!(&auth.members[0].type_, "String");
+        assert_eq!(&auth.members[1].name, "Token");
+        assert_eq!(&auth.members[1].type_, "ClientAuthToken");
+        assert_eq!(&auth.members[2].name, "Object");
+        assert_eq!(&auth.members[2].type_, "ClientAuthObject");
+        assert_eq!(structs[2].name, "ClientAuthToken");
+        assert_eq!(structs[2].members.len(), 2);
+
+        let schema_str = r#"
+            type: object
+            properties:
+              auth:
+                oneOf:
+                  - type: object
+                    required: [type, token]
+                    properties:
+                      type:
+                        type: string
+                        enum: [bearer]
+                      token:
+                        type: string
+                  - type: object
+                    required: [type]
+                    properties:
+                      type:
+                        type: string
+                        enum: [basic-auth]
+                      username:
+                        type: string
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Client", Cfg::default()).unwrap().output();
+
+        let auth = &structs[1];
+        assert_eq!(auth.name, "ClientAuth");
+        assert_eq!(auth.serde_annot, vec!["tag = \"type\""]);
+        assert_eq!(&auth.members[0].name, "bearer");
+        assert_eq!(&auth.members[0].type_, "ClientAuthBearer");
+        assert_eq!(&auth.members[1].name, "basic-auth");
+        assert_eq!(&auth.members[1].type_, "ClientAuthBasicAuth");
+
+        // the tag is not repeated inside the variant structs
+        let bearer = &structs[2];
+        assert_eq!(bearer.name, "ClientAuthBearer");
+        assert_eq!(bearer.members.len(), 1);
+        assert_eq!(&bearer.members[0].name, "token");
+        assert_eq!(&bearer.members[0].type_, "String");
+        let basic = &structs[3];
+        assert_eq!(basic.name, "ClientAuthBasicAuth");
+        assert_eq!(&basic.members[0].name, "username");
+        assert_eq!(&basic.members[0].type_, "Option<String>");
+    }
+
+    #[test]
+    fn schema_defaults() {
+        init();
+        let schema_str = r#"
+            type: object
+            required: [port]
+            properties:
+              port:
+                type: integer
+                format: int32
+                default: 8080
+              protocol:
+                type: string
+                enum: [TCP, UDP]
+                default: TCP
+              labels:
+                type: object
+                additionalProperties:
+                  type: string
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Endpoint", Cfg::default()).unwrap().output();
+        let root = &structs[0];
+        assert_eq!(&root.members[0].name, "labels");
+        assert_eq!(root.members[0].default, None);
+        assert_eq!(&root.members[1].name, "port");
+        assert_eq!(root.members[1].default, Some(serde_json::json!(8080)));
+        assert_eq!(&root.members[2].name, "protocol");
+        assert_eq!(root.members[2].default, Some(serde_json::json!("TCP")));
+        assert!(root.has_defaults());
+    }
+
+    #[test]
+    fn schema_defaults_without_literals() {
+        init();
+        let schema_str = r#"
+            type: object
+            properties:
+              backend:
+                type: object
+                required: [port]
+                default:
+                  name: web
+                properties:
+                  name:
+                    type: string
+                  port:
+                    type: integer
+                    format: int32
+              replicas:
+                type: integer
+                format: int32
+                default: "3"
+              target:
+                type: object
+                default:
+                  name: web
+                properties:
+                  name:
+                    type: string
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let output = analyze(schema, "Endpoint", Cfg::default())
+            .unwrap()
+            .schema_defaults(true);
+        assert_eq!(output.warnings(), [
+            r#"not emitting default of Endpoint.backend - {"name":"web"} cannot be written as Option<EndpointBackend>"#,
+            r#"not emitting default of Endpoint.replicas - "3" cannot be written as Option<i32>"#,
+        ]);
+        let structs = output.output();
+        let root = &structs[0];
+        assert_eq!(root.members[0].default, None);
+        assert_eq!(
+            root.members[2].default_expr(&structs).as_deref(),
+            Some(r#"Some(EndpointTarget { name: Some("web".to_string()) })"#)
+        );
+    }
+
+    #[test]
+    fn schema_constraints() {
+        init();
+        let schema_str = r#"
+            type: object
+            required: [ports]
+            properties:
+              ports:
+                type: array
+                minItems: 1
+                uniqueItems: true
+                items:
+                  type: integer
+                  format: int32
+              name:
+                type: string
+                maxLength: 63
+                pattern: ^[a-z]+$
+              namespace:
+                type: string
+                pattern: ^(?!kube-).*$
+              weight:
+                type: number
+                minimum: 0
+                exclusiveMinimum: true
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let output = analyze(schema.clone(), "Endpoint", Cfg::default()).unwrap();
+        let root = &output.output()[0];
+        assert_eq!(&root.members[0].name, "name");
+        assert_eq!(root.members[0].constraints, Constraints {
+            max_length: Some(63),
+            pattern: Some("^[a-z]+$".into()),
+            ..Constraints::default()
+        });
+        assert_eq!(&root.members[2].name, "ports");
+        assert_eq!(root.members[2].constraints, Constraints {
+            min_items: Some(1),
+            unique_items: true,
+            ..Constraints::default()
+        });
+        assert_eq!(&root.members[3].name, "weight");
+        assert_eq!(root.members[3].constraints.minimum, Some(0.0));
+        assert!(root.members[3].constraints.exclusive_minimum);
+
+        // patterns the regex crate does not support are dropped when validating
+        let output = analyze(schema.clone(), "Endpoint", Cfg::default())
+            .unwrap()
+            .constraints(true);
+        assert_eq!(output.warnings().len(), 1);
+        assert!(output.warnings()[0].starts_with("not validating pattern of Endpoint.namespace"));
+        let root = &output.output()[0];
+        assert_eq!(root.members[1].constraints.pattern, None);
+        assert_eq!(root.members[0].constraints.pattern, Some("^[a-z]+$".into()));
+
+        // and all constraints are dropped otherwise
+        let output = analyze(schema, "Endpoint", Cfg::default())
+            .unwrap()
+            .constraints(false);
+        assert!(output.output()[0]
+            .members
+            .iter()
+            .all(|m| m.constraints.is_empty()));
+    }
+
+    #[test]
+    fn validation_rules() {
+        init();
+        let schema_str = r#"
+            type: object
+            x-kubernetes-validations:
+            - rule: self.minReplicas <= self.replicas
+              message: minReplicas must not exceed replicas
+            properties:
+              minReplicas:
+                type: integer
+              replicas:
+                type: integer
+                x-kubernetes-validations:
+                - rule: self == oldSelf
+              routes:
+                type: object
+                x-kubernetes-validations:
+                - rule: self.size() <= 8
+                additionalProperties:
+                  type: object
+                  properties:
+                    path:
+                      type: string
+"#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Scaler", Cfg::default()).unwrap().output();
+        let root = &structs[0];
+        assert_eq!(root.rules.len(), 1);
+        assert_eq!(root.rules[0].rule, "self.minReplicas <= self.replicas");
+        assert!(root.members[0].rules.is_empty());
+        assert_eq!(root.members[1].rules[0].rule, "self == oldSelf");
+        assert_eq!(root.members[2].rules[0].rule, "self.size() <= 8");
+
+        // the rules of a map belong to the map, not the struct of its values
+        let routes = &structs[1];
+        assert_eq!(routes.name, "ScalerRoutes");
+        assert!(routes.rules.is_empty());
+    }
+
+    #[test]
+    fn boolean_in_additional_properties() {
+        // as found in argo-app
+        init();
+        let schema_str = r#"
+            properties:
+              options:
+                additionalProperties:
                   type: boolean
                 type: object
               patch:
@@ -1048,6 +2062,47 @@ type: object
         assert_eq!(&op.members[2].type_, "");
         assert_eq!(&op.members[3].name, "DoesNotExist");
         assert_eq!(&op.members[3].type_, "");
+        assert_eq!(op.default_variant, None);
+    }
+
+    #[test]
+    fn enum_string_with_default() {
+        init();
+        let schema_str = r#"
+      properties:
+        scheme:
+          enum:
+          - http
+          - https
+          default: https
+          type: string
+        mode:
+          enum:
+          - a
+          - b
+          default: c
+          type: string
+      type: object
+"#;
+
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let mut structs = analyze(schema, "Endpoint", Cfg::default())
+            .unwrap()
+            .rename()
+            .output();
+
+        // default does not name a variant
+        let mode = &structs[1];
+        assert_eq!(mode.name, "EndpointMode");
+        assert_eq!(mode.default_variant, None);
+        assert!(!mode.can_derive_default(&[]));
+
+        // default follows the renamed variant
+        let scheme = structs.remove(2);
+        assert_eq!(scheme.name, "EndpointScheme");
+        assert_eq!(&scheme.members[1].name, "Https");
+        assert_eq!(scheme.default_variant.as_deref(), Some("Https"));
+        assert!(scheme.can_derive_default(&structs));
     }
 
     #[test]
@@ -1326,7 +2381,6 @@ type: object
     }
 
     #[test]
-    #[ignore] // currently do not handle top level enums, and this has an integration test
     fn top_level_enum_with_integers() {
         init();
         let schema_str = r#"
@@ -1338,15 +2392,127 @@ type: object
         "#;
         let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
         println!("got schema {}", serde_yaml::to_string(&schema).unwrap());
-        let structs = analyze(schema, "StatusCode", Cfg::default()).unwrap().output();
+        let output = analyze(schema, "StatusCode", Cfg::default()).unwrap().rename();
+        let structs = output.output();
         println!("got {:?}", structs);
         let root = &structs[0];
         assert_eq!(root.name, "StatusCode");
         assert_eq!(root.level, 0);
         assert!(root.is_enum);
-        assert_eq!(&root.members[0].name, "301");
-        assert_eq!(&root.members[0].name, "302");
-        assert_eq!(&root.members[0].type_, "");
+        assert_eq!(root.repr.as_deref(), Some("i64"));
+        assert_eq!(&root.members[0].name, "Value301");
+        assert_eq!(root.members[0].original_name(), "301");
+        assert_eq!(&root.members[1].name, "Value302");
+        assert_eq!(&root.members[1].type_, "");
+        assert_eq!(root.default_variant.as_deref(), Some("Value302"));
+    }
+
+    #[test]
+    fn enums_in_items_and_values() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              headers:
+                additionalProperties:
+                  enum:
+                  - Set
+                  - Remove
+                  type: string
+                type: object
+              methods:
+                items:
+                  enum:
+                  - GET
+                  - POST
+                  type: string
+                type: array
+              priority:
+                enum:
+                - -1
+                - 1
+                format: int32
+                type: integer
+              protocol:
+                enum:
+                - HTTP
+                - null
+                nullable: true
+                type: string
+              weight:
+                enum:
+                - 0.5
+                - 1.0
+                type: number
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let output = analyze(schema, "Route", Cfg::default()).unwrap();
+        assert_eq!(output.warnings(), [
+            "not generating enum RouteSpecWeight - only strings and integers can be variants"
+        ]);
+        let structs = output.output();
+        let spec = &structs[1];
+        assert_eq!(
+            spec.members[0].type_,
+            "Option<BTreeMap<String, RouteSpecHeaders>>"
+        );
+        assert_eq!(spec.members[1].type_, "Option<Vec<RouteSpecMethods>>");
+        assert_eq!(spec.members[2].type_, "Option<RouteSpecPriority>");
+        assert_eq!(spec.members[3].type_, "Option<RouteSpecProtocol>");
+        assert_eq!(spec.members[4].type_, "Option<f64>");
+
+        let names = structs.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, [
+            "Route",
+            "RouteSpec",
+            "RouteSpecHeaders",
+            "RouteSpecMethods",
+            "RouteSpecPriority",
+            "RouteSpecProtocol"
+        ]);
+        assert!(structs[2..].iter().all(|c| c.is_enum));
+        assert_eq!(structs[4].repr.as_deref(), Some("i32"));
+        assert_eq!(structs[4].members[0].name, "-1");
+        // null is not a variant, but the None of the optional member
+        assert_eq!(structs[5].members.len(), 1);
+        assert_eq!(structs[5].repr, None);
+    }
+
+    #[test]
+    fn enum_integers_outside_their_format() {
+        init();
+        let schema_str = r#"
+        properties:
+          mask:
+            enum:
+            - 0
+            - 18446744073709551615
+            type: integer
+          port:
+            enum:
+            - 80
+            - 65536
+            format: uint16
+            type: integer
+          signal:
+            enum:
+            - -1
+            - 9
+            format: uint8
+            type: integer
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Process", Cfg::default()).unwrap().output();
+        assert_eq!(structs[1].name, "ProcessMask");
+        assert_eq!(structs[1].repr.as_deref(), Some("u64"));
+        assert_eq!(structs[2].name, "ProcessPort");
+        assert_eq!(structs[2].repr.as_deref(), Some("i64"));
+        assert_eq!(structs[3].name, "ProcessSignal");
+        assert_eq!(structs[3].repr.as_deref(), Some("i64"));
     }
 
     #[test]
@@ -1561,10 +2727,24 @@ type: object
 
         let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
 
-        let structs = analyze(schema, "Gateway", Cfg::default()).unwrap().output();
+        let structs = analyze(schema.clone(), "Gateway", Cfg::default())
+            .unwrap()
+            .output();
         assert_eq!(structs.len(), 1);
         assert_eq!(structs[0].members.len(), 1);
         assert_eq!(structs[0].members[0].type_, "Option<Vec<Condition>>");
+
+        // the deprecated flag still turns off conditions
+        #[allow(deprecated)]
+        let cfg = Cfg {
+            no_condition: true,
+            ..Cfg::default()
+        };
+        let structs = analyze(schema, "Gateway", cfg).unwrap().output();
+        assert_eq!(structs[0].members[0].type_, "Option<Vec<GatewayConditions>>");
+        #[allow(deprecated)]
+        let contains_conditions = structs[0].contains_conditions();
+        assert!(!contains_conditions);
     }
 
     #[test]
@@ -1633,71 +2813,263 @@ type: object
     }
 
     #[test]
-    fn lowercase_kind() {
+    fn uses_k8s_openapi_known_types() {
         init();
-
         let schema_str = r#"
         properties:
-          prop:
+          spec:
+            properties:
+              name:
+                type: string
+            type: object
+            x-kubernetes-map-type: atomic
+          status:
+            properties:
+              tolerations:
+                items:
+                  properties:
+                    effect:
+                      type: string
+                    key:
+                      type: string
+                    operator:
+                      type: string
+                    value:
+                      type: string
+                  type: object
+                type: array
+              selector:
+                properties:
+                  matchLabels:
+                    additionalProperties:
+                      type: string
+                    type: object
+                type: object
+                x-kubernetes-map-type: atomic
             type: object
-        required:
-        - prop
         type: object
         "#;
 
         let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
 
-        let structs = analyze(schema, "postgresql", Cfg::default()).unwrap().output();
-        assert_eq!(structs[0].members[0].type_, "PostgresqlProp");
+        let cfg = || Cfg {
+            known_types: KnownTypes::common(),
+            ..Cfg::default()
+        };
+        let structs = analyze(schema.clone(), "Pool", cfg()).unwrap().output();
+        // the spec is generated even when shaped like a LocalObjectReference
+        assert_eq!(structs.len(), 3);
+        assert_eq!(structs[1].name, "PoolSpec");
+        assert_eq!(structs[2].name, "PoolStatus");
+        assert_eq!(structs[2].members[0].type_, "Option<LabelSelector>");
+        assert_eq!(structs[2].members[1].type_, "Option<Vec<Toleration>>");
+
+        let mut cfg = cfg();
+        cfg.known_types.remove("Toleration");
+        let structs = analyze(schema.clone(), "Pool", cfg).unwrap().output();
+        assert_eq!(structs[2].members[1].type_, "Option<Vec<PoolStatusTolerations>>");
+        assert_eq!(structs[3].name, "PoolStatusTolerations");
+
+        // only conditions and object references are known by default
+        let structs = analyze(schema, "Pool", Cfg::default()).unwrap().output();
+        assert_eq!(structs[2].members[0].type_, "Option<PoolStatusSelector>");
+        assert_eq!(structs[2].members[1].type_, "Option<Vec<PoolStatusTolerations>>");
     }
 
     #[test]
-    fn nested_additional_properties_object() {
+    fn known_types_clashing_with_generated_types() {
         init();
-
-        // as found in `rook-cephcluster`
         let schema_str = r#"
         properties:
-          cephConfigFromSecret:
-            additionalProperties:
-              additionalProperties:
-                description: "SecretKeySelector selects a key of a Secret."
+          spec:
+            properties:
+              extra:
                 properties:
-                  key:
-                    description: "The key of the secret to select from.  Must be a valid secret key."
-                    type: "string"
                   name:
-                    default: ""
-                    description: "Name of the referent.\nThis field is effectively required, but due to backwards compatibility is\nallowed to be empty. Instances of this type with an empty value here are\nalmost certainly wrong.\nMore info: https://kubernetes.io/docs/concepts/overview/working-with-objects/names/#names"
-                    type: "string"
-                  optional:
-                    description: "Specify whether the Secret or its key must be defined"
-                    type: "boolean"
-                required:
-                  - "key"
-                type: "object"
-                x-kubernetes-map-type: "atomic"
-              type: "object"
-            description: "CephConfigFromSecret works exactly like CephConfig but takes config value from Secret Key reference."
-            nullable: true
-            type: "object"
+                    type: string
+                  valueFrom:
+                    properties:
+                      path:
+                        type: string
+                    type: object
+                type: object
+            type: object
         type: object
         "#;
+        let cfg = || Cfg {
+            known_types: KnownTypes::common(),
+            ..Cfg::default()
+        };
 
+        // known types are kept when merely starting like the kind
         let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema.clone(), "Env", cfg()).unwrap().output();
+        assert_eq!(structs[1].members[0].type_, "Option<EnvVar>");
+
+        // but generated when a generated type has their name, here `EnvVar` for `EnvSpecVar`
+        let mut schema = schema;
+        let spec = schema.properties.as_mut().unwrap().get_mut("spec").unwrap();
+        let var = serde_yaml::from_str("type: object\nproperties:\n  path:\n    type: string").unwrap();
+        spec.properties.as_mut().unwrap().insert("var".into(), var);
+        let structs = analyze(schema, "Env", cfg()).unwrap().output();
+        assert_eq!(structs[1].members[0].type_, "Option<EnvSpecExtra>");
+        assert_eq!(structs[1].members[1].type_, "Option<EnvSpecVar>");
+    }
 
-        let structs = analyze(schema, "CephClusterSpec", Cfg::default())
-            .unwrap()
-            .output();
-
-        // log::debug!("got: {:#?}", structs);
-
-        assert_eq!(structs.len(), 2);
-        assert_eq!(
-            structs[0].members[0].type_,
-            "Option<BTreeMap<String, BTreeMap<String, CephClusterSpecCephConfigFromSecret>>>"
+    #[test]
+    fn type_overrides() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              config:
+                properties:
+                  nested:
+                    properties:
+                      name:
+                        type: string
+                    type: object
+                type: object
+              mode:
+                enum:
+                - a
+                - b
+                type: string
+              name:
+                maxLength: 63
+                type: string
+              tags:
+                items:
+                  type: string
+                type: array
+              endpoints:
+                items:
+                  properties:
+                    port:
+                      type: integer
+                  type: object
+                type: array
+            type: object
+        type: object
+        "#;
+
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+
+        let cfg = Cfg {
+            type_overrides: vec![
+                TypeOverride::new("spec.config", "my_crate::Config"),
+                TypeOverride::new("PoolMode", "my_crate::Mode"),
+                TypeOverride::new("PoolSpecEndpoints", "my_crate::Endpoint"),
+                TypeOverride::new("spec.missing", "my_crate::Missing"),
+                // only named like a part of another type
+                TypeOverride::new("spec.conf", "my_crate::Conf"),
+                TypeOverride::new("spec.name", "my_crate::Name"),
+                TypeOverride::new("spec.tags", "my_crate::Tag"),
+            ],
+            ..Cfg::default()
+        };
+        let output = analyze(schema.clone(), "Pool", cfg).unwrap();
+        assert_eq!(output.warnings(), [
+            "type override for spec.missing did not match any property",
+            "type override for spec.conf did not match any property"
+        ]);
+        // no types are generated below the overridden properties
+        let structs = output.output();
+        assert_eq!(structs.len(), 2);
+        assert_eq!(structs[1].name, "PoolSpec");
+        assert_eq!(structs[1].members[0].type_, "Option<Config>");
+        assert_eq!(structs[1].members[1].type_, "Option<Vec<Endpoint>>");
+        assert_eq!(structs[1].members[2].type_, "Option<Mode>");
+        // scalar properties and items are overridden as well
+        assert_eq!(structs[1].members[3].type_, "Option<Name>");
+        assert_eq!(structs[1].members[4].type_, "Option<Vec<Tag>>");
+
+        let cfg = Cfg {
+            type_overrides: vec![TypeOverride::new("spec", "my_crate::Spec")],
+            ..Cfg::default()
+        };
+        assert!(analyze(schema, "Pool", cfg).is_err());
+    }
+
+    #[test]
+    fn lowercase_kind() {
+        init();
+
+        let schema_str = r#"
+        properties:
+          prop:
+            type: object
+        required:
+        - prop
+        type: object
+        "#;
+
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+
+        let structs = analyze(schema, "postgresql", Cfg::default()).unwrap().output();
+        assert_eq!(structs[0].members[0].type_, "PostgresqlProp");
+    }
+
+    #[test]
+    fn nested_additional_properties_object() {
+        init();
+
+        // as found in `rook-cephcluster`
+        let schema_str = r#"
+        properties:
+          cephConfigFromSecret:
+            additionalProperties:
+              additionalProperties:
+                description: "SecretKeySelector selects a key of a Secret."
+                properties:
+                  key:
+                    description: "The key of the secret to select from.  Must be a valid secret key."
+                    type: "string"
+                  name:
+                    default: ""
+                    description: "Name of the referent.\nThis field is effectively required, but due to backwards compatibility is\nallowed to be empty. Instances of this type with an empty value here are\nalmost certainly wrong.\nMore info: https://kubernetes.io/docs/concepts/overview/working-with-objects/names/#names"
+                    type: "string"
+                  optional:
+                    description: "Specify whether the Secret or its key must be defined"
+                    type: "boolean"
+                required:
+                  - "key"
+                type: "object"
+                x-kubernetes-map-type: "atomic"
+              type: "object"
+            description: "CephConfigFromSecret works exactly like CephConfig but takes config value from Secret Key reference."
+            nullable: true
+            type: "object"
+        type: object
+        "#;
+
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+
+        // the values are secret key selectors, which are generated without the common known types
+        let structs = analyze(schema.clone(), "CephClusterSpec", Cfg::default())
+            .unwrap()
+            .output();
+
+        // log::debug!("got: {:#?}", structs);
+
+        assert_eq!(structs.len(), 2);
+        assert_eq!(
+            structs[0].members[0].type_,
+            "Option<BTreeMap<String, BTreeMap<String, CephClusterSpecCephConfigFromSecret>>>"
         );
         assert_eq!(structs[1].name, "CephClusterSpecCephConfigFromSecret");
+
+        let cfg = Cfg {
+            known_types: KnownTypes::common(),
+            ..Cfg::default()
+        };
+        let structs = analyze(schema, "CephClusterSpec", cfg).unwrap().output();
+        assert_eq!(structs.len(), 1);
+        assert_eq!(
+            structs[0].members[0].type_,
+            "Option<BTreeMap<String, BTreeMap<String, SecretKeySelector>>>"
+        );
     }
 
     #[test]
@@ -1728,4 +3100,589 @@ type: object
         assert_eq!(map.name, "mounts");
         assert_eq!(map.type_, "Option<Vec<Vec<String>>>");
     }
+
+    #[test]
+    fn dedup_identical_types() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              endpoints:
+                items:
+                  properties:
+                    tlsConfig:
+                      properties:
+                        ca:
+                          properties:
+                            key:
+                              type: string
+                          type: object
+                        insecure:
+                          type: boolean
+                      type: object
+                  type: object
+                type: array
+              mode:
+                enum:
+                - active
+                - passive
+                type: string
+              proxy:
+                properties:
+                  mode:
+                    enum:
+                    - active
+                    - passive
+                    type: string
+                  tlsConfig:
+                    description: TLS settings of the proxy
+                    properties:
+                      ca:
+                        properties:
+                          key:
+                            type: string
+                        type: object
+                      insecure:
+                        type: boolean
+                    type: object
+                  timeout:
+                    properties:
+                      key:
+                        type: string
+                    type: object
+                type: object
+            type: object
+          status:
+            properties:
+              key:
+                type: string
+            type: object
+        type: object
+        "#;
+
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let output = analyze(schema, "Pool", Cfg::default()).unwrap().rename();
+        let names = |output: &[Container]| output.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(output.containers()), [
+            "Pool",
+            "PoolSpec",
+            "PoolSpecEndpoints",
+            "PoolSpecEndpointsTlsConfig",
+            "PoolSpecEndpointsTlsConfigCa",
+            "PoolSpecMode",
+            "PoolSpecProxy",
+            "PoolSpecProxyMode",
+            "PoolSpecProxyTimeout",
+            "PoolSpecProxyTlsConfig",
+            "PoolSpecProxyTlsConfigCa",
+            "PoolStatus",
+        ]);
+
+        // merging the ca types makes the tls configs identical, enums under the spec merge like other types,
+        // and the status is never merged
+        let structs = output.dedup(true).output();
+        assert_eq!(names(&structs), [
+            "Pool",
+            "PoolSpec",
+            "PoolSpecEndpoints",
+            "PoolSpecTlsConfig",
+            "PoolSpecProxyTimeout",
+            "PoolSpecMode",
+            "PoolSpecProxy",
+            "PoolStatus",
+        ]);
+        assert_eq!(structs[1].members[1].type_, "Option<PoolSpecMode>");
+        assert_eq!(structs[2].members[0].type_, "Option<PoolSpecTlsConfig>");
+        assert_eq!(structs[3].members[0].type_, "Option<PoolSpecProxyTimeout>");
+        assert_eq!(structs[6].members[0].type_, "Option<PoolSpecMode>");
+        assert_eq!(structs[6].members[1].type_, "Option<PoolSpecProxyTimeout>");
+        assert_eq!(structs[6].members[2].type_, "Option<PoolSpecTlsConfig>");
+    }
+
+    #[test]
+    fn embedded_resources() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              resources:
+                items:
+                  properties:
+                    base:
+                      type: object
+                      x-kubernetes-embedded-resource: true
+                      x-kubernetes-preserve-unknown-fields: true
+                  type: object
+                type: array
+              template:
+                properties:
+                  apiVersion:
+                    type: string
+                  metadata:
+                    properties:
+                      name:
+                        type: string
+                    type: object
+                type: object
+                x-kubernetes-embedded-resource: true
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+
+        let structs = analyze(schema.clone(), "Composition", Cfg::default())
+            .unwrap()
+            .output();
+        assert_eq!(structs.len(), 3);
+        assert_eq!(structs[1].members[1].type_, "Option<DynamicObject>");
+        assert_eq!(structs[2].name, "CompositionSpecResources");
+        assert_eq!(structs[2].members[0].type_, "Option<DynamicObject>");
+
+        let cfg = Cfg {
+            embedded_resources: EmbeddedResources::Generated,
+            ..Cfg::default()
+        };
+        let structs = analyze(schema, "Composition", cfg).unwrap().output();
+        let names = structs.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, [
+            "Composition",
+            "CompositionSpec",
+            "CompositionSpecResources",
+            "CompositionSpecTemplate"
+        ]);
+        assert_eq!(
+            structs[2].members[0].type_,
+            "Option<BTreeMap<String, serde_json::Value>>"
+        );
+        assert_eq!(structs[3].members[1].type_, "Option<ObjectMeta>");
+    }
+
+    #[test]
+    fn list_types() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              hosts:
+                items:
+                  type: string
+                type: array
+                x-kubernetes-list-type: set
+              ports:
+                items:
+                  properties:
+                    name:
+                      type: string
+                    port:
+                      type: integer
+                  required:
+                  - port
+                  type: object
+                type: array
+                x-kubernetes-list-map-keys:
+                - port
+                - name
+                x-kubernetes-list-type: map
+              rules:
+                items:
+                  properties:
+                    match:
+                      properties:
+                        path:
+                          type: string
+                      type: object
+                  type: object
+                type: array
+                x-kubernetes-list-map-keys:
+                - match
+                x-kubernetes-list-type: map
+              weights:
+                items:
+                  type: number
+                type: array
+                x-kubernetes-list-type: set
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let analyzed = || {
+            analyze(schema.clone(), "Backend", Cfg::default())
+                .unwrap()
+                .rename()
+        };
+
+        let spec = &analyzed().list_types(false).output()[1];
+        assert_eq!(spec.members[0].type_, "Option<Vec<String>>");
+        assert!(spec.members.iter().all(|m| m.list_type.is_none()));
+
+        let output = analyzed().list_types(true);
+        assert_eq!(output.warnings(), [
+            "not generating accessors for BackendSpec.rules - key match is not a string, number or boolean"
+        ]);
+        let spec = &output.output()[1];
+        assert_eq!(spec.members[0].type_, "Option<BTreeSet<String>>");
+        assert_eq!(
+            spec.members[1].list_type,
+            Some(ListType::Map(vec!["port".to_string(), "name".to_string()]))
+        );
+        assert_eq!(spec.members[2].list_type, None);
+        // floats are not ordered
+        assert_eq!(spec.members[3].type_, "Option<Vec<f64>>");
+    }
+
+    #[test]
+    fn string_formats() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              addresses:
+                items:
+                  format: ipv4
+                  type: string
+                type: array
+              email:
+                format: email
+                type: string
+              limits:
+                additionalProperties:
+                  anyOf:
+                  - type: integer
+                  - type: string
+                  pattern: ^(\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))(([KMGTPE]i)|[numkMGTPE]|([eE](\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))))?$
+                  x-kubernetes-int-or-string: true
+                type: object
+              requests:
+                items:
+                  anyOf:
+                  - type: integer
+                  - type: string
+                  pattern: ^(\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))(([KMGTPE]i)|[numkMGTPE]|([eE](\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))))?$
+                  x-kubernetes-int-or-string: true
+                type: array
+              uid:
+                format: uuid
+                type: string
+            required:
+            - uid
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+
+        let spec = &analyze(schema.clone(), "Network", Cfg::default())
+            .unwrap()
+            .output()[1];
+        assert_eq!(spec.members[0].type_, "Option<Vec<String>>");
+        assert_eq!(spec.members[2].type_, "Option<BTreeMap<String, IntOrString>>");
+        assert_eq!(spec.members[3].type_, "Option<Vec<IntOrString>>");
+        assert_eq!(spec.members[4].type_, "String");
+
+        let cfg = Cfg {
+            format_types: FormatTypes::default(),
+            ..Cfg::default()
+        };
+        let spec = &analyze(schema, "Network", cfg).unwrap().output()[1];
+        assert_eq!(spec.members[0].type_, "Option<Vec<Ipv4Addr>>");
+        assert_eq!(spec.members[1].type_, "Option<String>");
+        assert_eq!(spec.members[2].type_, "Option<BTreeMap<String, Quantity>>");
+        assert_eq!(spec.members[3].type_, "Option<Vec<Quantity>>");
+        assert_eq!(spec.members[4].type_, "Uuid");
+    }
+
+    #[test]
+    fn string_format_constraints() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              gateway:
+                format: ipv4
+                pattern: ^10\.
+                type: string
+              hostname:
+                format: hostname
+                maxLength: 253
+                type: string
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let cfg = Cfg {
+            format_types: FormatTypes::default(),
+            ..Cfg::default()
+        };
+
+        // only the generated newtypes of strings are checked like strings
+        let output = analyze(schema, "Network", cfg).unwrap().constraints(true);
+        assert_eq!(output.warnings(), [
+            "not validating length or pattern of NetworkSpec.gateway - Ipv4Addr is not a string"
+        ]);
+        let spec = &output.output()[1];
+        assert_eq!(spec.members[0].constraints.pattern, None);
+        assert_eq!(spec.members[1].type_, "Option<Hostname>");
+        assert_eq!(spec.members[1].constraints.max_length, Some(253));
+    }
+
+    #[test]
+    fn datetime_backends() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              holidays:
+                items:
+                  format: date
+                  type: string
+                type: array
+              runs:
+                items:
+                  format: date-time
+                  type: string
+                type: array
+              startTime:
+                format: date-time
+                type: string
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+
+        let types = |datetime| {
+            let cfg = Cfg {
+                datetime,
+                ..Cfg::default()
+            };
+            let spec = &analyze(schema.clone(), "Schedule", cfg).unwrap().output()[1];
+            (spec.members[0].type_.clone(), spec.members[2].type_.clone())
+        };
+        let strings = ("Option<Vec<String>>".into(), "Option<String>".into());
+        assert_eq!(types(DateTimeBackend::String), strings);
+        assert_eq!(
+            types(DateTimeBackend::Chrono),
+            ("Option<Vec<NaiveDate>>".into(), "Option<DateTime<Utc>>".into())
+        );
+        assert_eq!(
+            types(DateTimeBackend::Jiff),
+            ("Option<Vec<Date>>".into(), "Option<Timestamp>".into())
+        );
+        assert_eq!(
+            types(DateTimeBackend::K8s),
+            ("Option<Vec<String>>".into(), "Option<Time>".into())
+        );
+
+        // date-times of time are serialized as RFC 3339, but cannot be in lists
+        let cfg = Cfg {
+            datetime: DateTimeBackend::Time,
+            ..Cfg::default()
+        };
+        let output = analyze(schema, "Schedule", cfg)
+            .unwrap()
+            .datetime(DateTimeBackend::Time);
+        assert_eq!(output.warnings(), [
+            "not typing ScheduleSpec.runs as OffsetDateTime - only fields and options can be"
+        ]);
+        let spec = &output.output()[1];
+        assert_eq!(spec.members[1].type_, "Option<Vec<String>>");
+        assert_eq!(spec.members[2].type_, "Option<OffsetDateTime>");
+        assert_eq!(
+            spec.members[2].serde_annot.last().unwrap(),
+            r#"with = "time::serde::rfc3339::option""#
+        );
+        assert!(spec.uses_datetime(DateTimeBackend::Time));
+        assert!(spec.uses_date(DateTimeBackend::Time));
+        assert!(!spec.uses_datetime(DateTimeBackend::Chrono));
+    }
+
+    #[test]
+    fn nullable_members() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              pausedAt:
+                nullable: true
+                type: string
+              replicas:
+                nullable: true
+                type: integer
+              revisionHistoryLimit:
+                type: integer
+            required:
+            - pausedAt
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+
+        // required nullable members accept null, and optional ones treat it as absent
+        let spec = &analyze(schema.clone(), "Rollout", Cfg::default())
+            .unwrap()
+            .output()[1];
+        assert_eq!(spec.members[0].type_, "Option<String>");
+        assert!(spec.members[0].serde_annot.is_empty());
+        assert_eq!(spec.members[1].type_, "Option<i64>");
+        assert_eq!(spec.members[2].type_, "Option<i64>");
+        assert!(!spec.uses_explicit_null());
+
+        let cfg = Cfg {
+            explicit_null: true,
+            ..Cfg::default()
+        };
+        let spec = &analyze(schema, "Rollout", cfg).unwrap().output()[1];
+        assert_eq!(spec.members[0].type_, "Option<String>");
+        assert_eq!(spec.members[1].type_, "Option<Option<i64>>");
+        assert_eq!(
+            spec.members[1].serde_annot.last().unwrap(),
+            r#"deserialize_with = "deserialize_some""#
+        );
+        assert_eq!(spec.members[2].type_, "Option<i64>");
+        assert!(spec.uses_explicit_null());
+    }
+
+    #[test]
+    fn tuple_items() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              bounds:
+                items:
+                - format: int32
+                  type: integer
+                - type: string
+                type: array
+              points:
+                items:
+                  items:
+                  - type: string
+                  - properties:
+                      x:
+                        type: number
+                    type: object
+                  type: array
+                type: array
+            required:
+            - bounds
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Range", Cfg::default()).unwrap().output();
+
+        // positions are typed in turn, and structs are named after their position
+        let spec = &structs[1];
+        assert_eq!(spec.members[0].type_, "(i32, String)");
+        assert_eq!(spec.members[1].type_, "Option<Vec<(String, RangeSpecPoints1)>>");
+        assert_eq!(structs[2].name, "RangeSpecPoints1");
+        assert_eq!(structs[2].members[0].type_, "Option<f64>");
+        assert_eq!(structs.len(), 3);
+    }
+
+    #[test]
+    fn all_of_branches() {
+        init();
+        let schema_str = r#"
+        properties:
+          spec:
+            allOf:
+            - properties:
+                target:
+                  properties:
+                    name:
+                      type: string
+                  type: object
+              required:
+              - target
+            - properties:
+                replicas:
+                  type: integer
+                target:
+                  properties:
+                    namespace:
+                      type: string
+                  required:
+                  - name
+                  type: object
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let structs = analyze(schema, "Deploy", Cfg::default()).unwrap().output();
+
+        // the branches form one struct, with the properties of each branch merged
+        let spec = &structs[1];
+        assert_eq!(spec.members[0].name, "replicas");
+        assert_eq!(spec.members[1].type_, "DeploySpecTarget");
+        let target = &structs[2];
+        assert_eq!(target.members[0].type_, "String");
+        assert_eq!(target.members[1].type_, "Option<String>");
+
+        // a property typed differently by two branches cannot be merged
+        let schema: JSONSchemaProps = serde_yaml::from_str(
+            r#"
+        properties:
+          spec:
+            allOf:
+            - properties:
+                target:
+                  type: object
+            - properties:
+                target:
+                  type: string
+            type: object
+        type: object
+        "#,
+        )
+        .unwrap();
+        let error = analyze(schema, "Deploy", Cfg::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "conflicting types for Deploy.spec.target in allOf: object and string"
+        );
+
+        // as is any other keyword given different values, while documentation is taken from the first branch
+        let conflict = |first: &str, second: &str| {
+            let schema_str = format!(
+                r#"
+        properties:
+          spec:
+            allOf:
+            - properties:
+                port:
+                  description: the first
+                  type: integer
+                  {first}
+            - properties:
+                port:
+                  description: the second
+                  type: integer
+                  {second}
+            type: object
+        type: object
+        "#
+            );
+            let schema: JSONSchemaProps = serde_yaml::from_str(&schema_str).unwrap();
+            analyze(schema, "Deploy", Cfg::default())
+                .err()
+                .map(|e| e.to_string())
+        };
+        assert_eq!(conflict("format: int32", "format: int32"), None);
+        assert_eq!(
+            conflict("format: int32", "format: int64"),
+            Some(r#"conflicting format for Deploy.spec.port in allOf: "int32" and "int64""#.to_string())
+        );
+        assert_eq!(
+            conflict("enum: [80, 443]", "enum: [8080]"),
+            Some("conflicting enum for Deploy.spec.port in allOf: [80,443] and [8080]".to_string())
+        );
+    }
 }
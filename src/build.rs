@@ -0,0 +1,215 @@
+//! Helpers for generating types from a cargo build script
+//!
+//! ```no_run
+//! // build.rs
+//! fn main() -> anyhow::Result<()> {
+//!     let generator = kopium::TypeGenerator::builder().emit_docs(true).build();
+//!
+//!     kopium::build::Builder::new("src/crds")
+//!         .generator(generator)
+//!         .input("crds/*.yaml")
+//!         .kind("ServiceMonitor")
+//!         .generate()?;
+//!     Ok(())
+//! }
+//! ```
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+
+use crate::{crds_from_path, schemas_from_path, Layout, SchemaMode, TypeGenerator};
+
+/// Generates types for CRDs on disk into a directory of modules, for use in `build.rs`
+///
+/// Every input file is reported to cargo with `cargo::rerun-if-changed`, so the build script
+/// only reruns when the CRDs change. Files are written like [`write_if_changed`](crate::write_if_changed)
+/// writes them, so unchanged files keep their modification time.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    generator: TypeGenerator,
+    inputs: Vec<String>,
+    kinds: Vec<String>,
+    groups: Vec<String>,
+    output_dir: PathBuf,
+    layout: Layout,
+    rerun_if_changed: bool,
+}
+
+impl Builder {
+    /// Generate into `output_dir`, relative to the crate root when run from a build script
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            generator: TypeGenerator::default(),
+            inputs: vec![],
+            kinds: vec![],
+            groups: vec![],
+            output_dir: output_dir.into(),
+            layout: Layout::default(),
+            rerun_if_changed: true,
+        }
+    }
+
+    /// Use this generator configuration
+    pub fn generator(mut self, generator: TypeGenerator) -> Self {
+        self.generator = generator;
+        self
+    }
+
+    /// Read CRDs from a file, a directory, or all files matching a glob pattern (e.g. `crds/*.yaml`)
+    pub fn input(mut self, input: impl Into<String>) -> Self {
+        self.inputs.push(input.into());
+        self
+    }
+
+    /// Only generate CRDs of this kind
+    ///
+    /// Can be given multiple times to generate several kinds.
+    pub fn kind(mut self, kind: impl Into<String>) -> Self {
+        self.kinds.push(kind.into());
+        self
+    }
+
+    /// Only generate CRDs in this API group
+    ///
+    /// Can be given multiple times to generate several groups.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.groups.push(group.into());
+        self
+    }
+
+    /// Lay out the generated files according to `layout`
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Whether to print `cargo::rerun-if-changed` lines for all inputs (enabled by default)
+    ///
+    /// Glob patterns also watch the directory they match in, so that new files matching them rerun the build script.
+    pub fn rerun_if_changed(mut self, enabled: bool) -> Self {
+        self.rerun_if_changed = enabled;
+        self
+    }
+
+    /// Read, filter and generate all CRDs, returning the paths of the files that were written
+    ///
+    /// All files are generated and staged before any of them is replaced, so a failure to generate or
+    /// write them leaves the output directory untouched. Only renaming the staged files into place can
+    /// fail midway, and leave a mix of old and new modules behind.
+    ///
+    /// Files of CRDs that are no longer generated are left in the output directory. The generated `mod.rs`
+    /// no longer declares them, so they are not compiled, and can be removed.
+    pub fn generate(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut crds = vec![];
+        let mut generator = self.generator.clone();
+        if self.rerun_if_changed {
+            // files added to a directory a pattern matches in change it, and do not match before they exist
+            for dir in self.inputs.iter().filter_map(|input| glob_dir(input)) {
+                println!("cargo::rerun-if-changed={}", dir.display());
+            }
+        }
+        for path in self.input_paths()? {
+            if self.rerun_if_changed {
+                println!("cargo::rerun-if-changed={}", path.display());
+            }
+            crds.extend(crds_from_path(&path)?);
+            if generator.schema_mode == SchemaMode::Preserved {
+                generator.original_schemas.extend(schemas_from_path(&path)?);
+            }
+        }
+
+        crds.retain(|crd| self.is_selected(crd));
+        if crds.is_empty() {
+            anyhow::bail!("No CustomResourceDefinitions matched the inputs and filters");
+        }
+
+        generator.write_files_for(&crds, &self.output_dir, self.layout, None::<String>)
+    }
+
+    /// All files and directories named by the inputs, with glob patterns expanded
+    fn input_paths(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut paths = vec![];
+        for input in &self.inputs {
+            if Path::new(input).exists() {
+                paths.push(PathBuf::from(input));
+                continue;
+            }
+
+            let matches = glob::glob(input)
+                .with_context(|| format!("Invalid input pattern {}", input))?
+                .collect::<Result<Vec<_>, _>>()?;
+            if matches.is_empty() {
+                anyhow::bail!("No files found for input {}", input);
+            }
+            paths.extend(matches);
+        }
+
+        Ok(paths)
+    }
+
+    fn is_selected(&self, crd: &CustomResourceDefinition) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&crd.spec.names.kind))
+            && (self.groups.is_empty() || self.groups.contains(&crd.spec.group))
+    }
+}
+
+/// The directory a glob pattern matches files in (e.g. `crds` for `crds/**/*.yaml`)
+///
+/// None for paths without glob characters, and for patterns matching in the crate root, which holds the build
+/// output.
+fn glob_dir(input: &str) -> Option<PathBuf> {
+    if Path::new(input).exists() {
+        return None;
+    }
+    let is_pattern = |c: &Component| c.as_os_str().to_string_lossy().contains(['*', '?', '[']);
+    let dir = Path::new(input)
+        .components()
+        .take_while(|c| !is_pattern(c))
+        .collect::<PathBuf>();
+    let has_pattern = Path::new(input).components().any(|c| is_pattern(&c));
+    (has_pattern && dir.components().next().is_some()).then_some(dir)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{glob_dir, Builder};
+
+    #[test]
+    fn generates_filtered_crds() {
+        let dir = std::env::temp_dir().join(format!("kopium-build-{}", std::process::id()));
+
+        let builder = Builder::new(&dir)
+            .input("tests/cmd/generate/crds/*.clux.dev.yaml")
+            .group("clux.dev")
+            .kind("Foo")
+            .kind("MultiVersion")
+            .rerun_if_changed(false);
+
+        let written = builder.generate().unwrap();
+        let mut names = written
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["foo.rs", "mod.rs", "multiversion.rs"]);
+
+        // nothing changed, so nothing is rewritten
+        assert!(builder.generate().unwrap().is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn watches_the_directories_of_patterns() {
+        assert_eq!(glob_dir("crds/*.yaml"), Some(PathBuf::from("crds")));
+        assert_eq!(
+            glob_dir("deploy/crds/**/*.yaml"),
+            Some(PathBuf::from("deploy/crds"))
+        );
+        assert_eq!(glob_dir("*.yaml"), None);
+        assert_eq!(glob_dir("crds/foo.yaml"), None);
+    }
+}
@@ -0,0 +1,246 @@
+//! Writing generated types into a directory tree of modules
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+/// Directory layout of generated files
+#[derive(
+    // std
+    Eq,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Layout {
+    /// One file per CRD in the output directory (e.g. `servicemonitor.rs`)
+    #[default]
+    Flat,
+
+    /// One file per CRD version, nested by group and version (e.g. `monitoring_coreos_com/v1/servicemonitor.rs`)
+    GroupVersion,
+}
+
+/// A generated file, with a path relative to the output directory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Write `contents` to `path` unless the file already has these exact contents
+///
+/// The file is replaced atomically, so readers never see a partially written file.
+/// Missing parent directories are created. Returns whether the file was written.
+pub fn write_if_changed(path: impl AsRef<Path>, contents: &str) -> anyhow::Result<bool> {
+    let written = write_all_if_changed([(path.as_ref().to_path_buf(), contents)])?;
+    Ok(!written.is_empty())
+}
+
+/// Write every file whose contents changed, staging all of them before replacing any
+///
+/// A failure while staging leaves every file untouched. Each file is then replaced atomically, so only a failure
+/// to rename a staged file into place can leave a mix of old and new files behind.
+/// Missing parent directories are created. Returns the paths of the files that were written.
+pub(crate) fn write_all_if_changed<'a>(
+    files: impl IntoIterator<Item = (PathBuf, &'a str)>,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut staged = vec![];
+    for (path, contents) in files {
+        if std::fs::read_to_string(&path).is_ok_and(|existing| existing == contents) {
+            log::debug!("{} is up to date", path.display());
+            continue;
+        }
+        match stage(&path, contents) {
+            Ok(tmp) => staged.push((tmp, path)),
+            Err(err) => {
+                for (tmp, _) in staged {
+                    let _ = std::fs::remove_file(tmp);
+                }
+                return Err(err);
+            }
+        }
+    }
+
+    // renaming within a filesystem is atomic
+    let mut written = vec![];
+    for (tmp, path) in staged {
+        std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Write `contents` next to `path`, returning the path of the staged file
+fn stage(path: &Path, contents: &str) -> anyhow::Result<PathBuf> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp = path.with_file_name(tmp_name);
+    std::fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+
+    Ok(tmp)
+}
+
+/// A module declaration in a generated `mod.rs`
+struct ModuleDecl {
+    name: String,
+    attrs: String,
+}
+
+/// Generated files along with the modules to declare in each directory's `mod.rs`
+#[derive(Default)]
+pub(crate) struct ModuleTree {
+    files: Vec<GeneratedFile>,
+    modules: BTreeMap<PathBuf, Vec<ModuleDecl>>,
+}
+
+impl ModuleTree {
+    /// Add a module file below the given parent modules
+    ///
+    /// The `attrs` (doc comments and attributes) are written above the module declaration.
+    /// Raw identifiers (e.g. `r#type`) are declared as such, in a file without the `r#` prefix.
+    pub(crate) fn insert(
+        &mut self,
+        parents: &[String],
+        name: &str,
+        attrs: String,
+        contents: String,
+    ) -> anyhow::Result<()> {
+        let mut dir = PathBuf::new();
+        for parent in parents {
+            self.declare(&dir, parent, String::new());
+            dir.push(parent.trim_start_matches("r#"));
+        }
+
+        let path = dir.join(name.trim_start_matches("r#")).with_extension("rs");
+        if self.files.iter().any(|file| file.path == path) {
+            anyhow::bail!("Multiple CRDs would be generated into {}", path.display());
+        }
+
+        self.declare(&dir, name, attrs);
+        self.files.push(GeneratedFile { path, contents });
+
+        Ok(())
+    }
+
+    fn declare(&mut self, dir: &Path, name: &str, attrs: String) {
+        let decls = self.modules.entry(dir.to_path_buf()).or_default();
+        if !decls.iter().any(|decl| decl.name == name) {
+            decls.push(ModuleDecl {
+                name: name.to_string(),
+                attrs,
+            });
+        }
+    }
+
+    /// All module files plus a `mod.rs` for every directory, each starting with `header`
+    pub(crate) fn into_files(self, header: &str) -> Vec<GeneratedFile> {
+        let mut files = self
+            .files
+            .into_iter()
+            .map(|file| GeneratedFile {
+                contents: format!("{}{}", header, file.contents),
+                ..file
+            })
+            .collect::<Vec<_>>();
+
+        for (dir, mut decls) in self.modules {
+            decls.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let mut contents = header.to_string();
+            for decl in decls {
+                contents.push_str(&decl.attrs);
+                contents.push_str(&format!("pub mod {};\n", decl.name));
+            }
+
+            files.push(GeneratedFile {
+                path: dir.join("mod.rs"),
+                contents,
+            });
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{write_all_if_changed, write_if_changed, ModuleTree};
+    use std::path::PathBuf;
+
+    #[test]
+    fn module_tree() {
+        let mut tree = ModuleTree::default();
+        let group = vec!["example_com".to_string(), "v1".to_string()];
+        tree.insert(&group, "foo", String::new(), "// foo\n".into())
+            .unwrap();
+        tree.insert(&group, "bar", "#[deprecated]\n".into(), "// bar\n".into())
+            .unwrap();
+        assert!(tree.insert(&group, "bar", String::new(), String::new()).is_err());
+
+        let files = tree.into_files("// header\n");
+        let paths = files.iter().map(|f| f.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths, vec![
+            PathBuf::from("example_com/mod.rs"),
+            PathBuf::from("example_com/v1/bar.rs"),
+            PathBuf::from("example_com/v1/foo.rs"),
+            PathBuf::from("example_com/v1/mod.rs"),
+            PathBuf::from("mod.rs"),
+        ]);
+        assert_eq!(files[0].contents, "// header\npub mod v1;\n");
+        assert_eq!(files[1].contents, "// header\n// bar\n");
+        assert_eq!(
+            files[3].contents,
+            "// header\n#[deprecated]\npub mod bar;\npub mod foo;\n"
+        );
+        assert_eq!(files[4].contents, "// header\npub mod example_com;\n");
+
+        let mut tree = ModuleTree::default();
+        tree.insert(&[], "r#type", String::new(), "// type\n".into())
+            .unwrap();
+        let files = tree.into_files("");
+        assert_eq!(files[0].path, PathBuf::from("mod.rs"));
+        assert_eq!(files[0].contents, "pub mod r#type;\n");
+        assert_eq!(files[1].path, PathBuf::from("type.rs"));
+    }
+
+    #[test]
+    fn writes_only_changed_files() {
+        let dir = std::env::temp_dir().join(format!("kopium-files-{}", std::process::id()));
+        let path = dir.join("nested").join("foo.rs");
+
+        assert!(write_if_changed(&path, "a").unwrap());
+        assert!(!write_if_changed(&path, "a").unwrap());
+        assert!(write_if_changed(&path, "b").unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "b");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_writes_leave_all_files_untouched() {
+        let dir = std::env::temp_dir().join(format!("kopium-staged-{}", std::process::id()));
+        let path = dir.join("foo.rs");
+        assert!(write_if_changed(&path, "a").unwrap());
+
+        // a file cannot be the parent directory of another
+        let blocked = path.join("bar.rs");
+        assert!(write_all_if_changed([(path.clone(), "b"), (blocked, "c")]).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
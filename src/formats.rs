@@ -0,0 +1,290 @@
+//! Registry of types used for string schemas with a well-known `format`
+use std::str::FromStr;
+
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::JSONSchemaProps;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::{
+    overrides::{path_import, path_type_name, split_path_assignment},
+    render::parse_item,
+    Container,
+};
+
+/// The pattern controller-gen declares for `resource.Quantity` properties
+const QUANTITY_PATTERN: &str = r"^(\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))(([KMGTPE]i)|[numkMGTPE]|([eE](\+|-)?(([0-9]+(\.[0-9]*)?)|(\.[0-9]+))))?$";
+
+/// Newtypes generated for formats without a common Rust type, along with their documentation
+const NEWTYPES: [(&str, &str); 4] = [
+    ("Cidr", "An IP network in CIDR notation (e.g. `10.0.0.0/8`)"),
+    ("Duration", "A duration in the Go syntax (e.g. `1h30m`)"),
+    ("Hostname", "An RFC 1123 hostname (e.g. `example.com`)"),
+    ("Uuid", "A UUID (e.g. `f81d4fae-7dec-11d0-a765-00a0c91e6bf6`)"),
+];
+
+/// A Rust type used for string schemas with a `format`, rather than `String`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FormatType {
+    /// The `format` of the schema (e.g. `byte`)
+    ///
+    /// `quantity` also matches `x-kubernetes-int-or-string` schemas with the pattern of a Kubernetes quantity.
+    pub format: String,
+    /// Path of the type to use (e.g. `k8s_openapi::ByteString`)
+    ///
+    /// A plain `Cidr`, `Duration`, `Hostname` or `Uuid` is a newtype of a string generated along with the types.
+    pub path: String,
+}
+
+impl FormatType {
+    /// Use the type at `path` for strings with `format`.
+    pub fn new(format: impl Into<String>, path: impl Into<String>) -> Self {
+        FormatType {
+            format: format.into(),
+            path: path.into(),
+        }
+    }
+
+    /// Name of the type in generated code, as for a [`TypeOverride`](crate::TypeOverride::type_name).
+    pub fn type_name(&self) -> &str {
+        path_type_name(&self.path)
+    }
+
+    /// The path to re-export from the prelude, if the type needs an import.
+    pub fn import(&self) -> Option<&str> {
+        path_import(&self.path)
+    }
+
+    /// Whether the type is a newtype generated along with the types
+    pub(crate) fn is_newtype(&self) -> bool {
+        is_newtype(&self.path)
+    }
+}
+
+/// Whether a type name is that of a newtype of a string generated for a format (e.g. `Hostname`)
+pub(crate) fn is_newtype(name: &str) -> bool {
+    NEWTYPES.iter().any(|(newtype, _)| *newtype == name)
+}
+
+impl FromStr for FormatType {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (format, path) = split_path_assignment(value, "format type", "format")?;
+        Ok(FormatType::new(format, path))
+    }
+}
+
+/// A registry of [`FormatType`]s, with at most one type per format
+///
+/// The default registry maps common formats to types from `k8s_openapi` and `std`, or to generated newtypes:
+///
+/// | format | type |
+/// |--------|------|
+/// | `byte` | `k8s_openapi::ByteString` |
+/// | `quantity` | `k8s_openapi::apimachinery::pkg::api::resource::Quantity` |
+/// | `ipv4` | `std::net::Ipv4Addr` |
+/// | `ipv6` | `std::net::Ipv6Addr` |
+/// | `cidr` | `Cidr` |
+/// | `duration` | `Duration` |
+/// | `hostname` | `Hostname` |
+/// | `uuid` | `Uuid` |
+///
+/// ```
+/// use kopium::{FormatType, FormatTypes};
+///
+/// let mut format_types = FormatTypes::default();
+/// format_types.register(FormatType::new("uuid", "uuid::Uuid"));
+/// let generator = kopium::TypeGenerator::builder()
+///     .string_formats(true)
+///     .format_types(format_types)
+///     .build();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatTypes(Vec<FormatType>);
+
+impl Default for FormatTypes {
+    fn default() -> Self {
+        Self(vec![
+            FormatType::new("byte", "k8s_openapi::ByteString"),
+            FormatType::new(
+                "quantity",
+                "k8s_openapi::apimachinery::pkg::api::resource::Quantity",
+            ),
+            FormatType::new("ipv4", "std::net::Ipv4Addr"),
+            FormatType::new("ipv6", "std::net::Ipv6Addr"),
+            FormatType::new("cidr", "Cidr"),
+            FormatType::new("duration", "Duration"),
+            FormatType::new("hostname", "Hostname"),
+            FormatType::new("uuid", "Uuid"),
+        ])
+    }
+}
+
+impl FormatTypes {
+    /// A registry without any types
+    pub fn empty() -> Self {
+        Self(vec![])
+    }
+
+    /// Add a type, replacing the type of the same format
+    pub fn register(&mut self, format_type: FormatType) {
+        self.0.retain(|existing| existing.format != format_type.format);
+        self.0.push(format_type);
+    }
+
+    /// The type of strings with this format
+    pub fn get(&self, format: &str) -> Option<&FormatType> {
+        self.0.iter().find(|format_type| format_type.format == format)
+    }
+
+    /// The type of a string (or `x-kubernetes-int-or-string`) schema, if its format is registered
+    pub fn find(&self, schema: &JSONSchemaProps) -> Option<&FormatType> {
+        if schema.pattern.as_deref() == Some(QUANTITY_PATTERN) {
+            return self.get("quantity");
+        }
+        self.get(schema.format.as_deref()?)
+    }
+
+    /// The registered types
+    pub fn iter(&self) -> impl Iterator<Item = &FormatType> {
+        self.0.iter()
+    }
+}
+
+/// The newtypes used by the rendered containers, emitted once per rendered module
+pub(crate) fn support_items(
+    containers: &[&Container],
+    format_types: &FormatTypes,
+    json_schema: bool,
+) -> anyhow::Result<Vec<syn::Item>> {
+    let mut derives = vec![
+        "Serialize",
+        "Deserialize",
+        "Clone",
+        "Debug",
+        "Default",
+        "PartialEq",
+        "Eq",
+        "Hash",
+        "PartialOrd",
+        "Ord",
+    ];
+    if json_schema {
+        derives.push("JsonSchema");
+    }
+    let derives = derives
+        .into_iter()
+        .map(|derive| format_ident!("{}", derive))
+        .collect::<Vec<_>>();
+
+    let mut items = vec![];
+    for format_type in format_types.iter().filter(|f| f.is_newtype()) {
+        if !containers.iter().any(|c| c.uses_type(&format_type.path)) {
+            continue;
+        }
+        let (name, docs) = NEWTYPES
+            .iter()
+            .find(|(name, _)| *name == format_type.path)
+            .unwrap();
+        let docs = format!(" {docs}");
+        let name = format_ident!("{}", name);
+        items.push(parse_item(quote! {
+            #[doc = #docs]
+            #[derive(#(#derives),*)]
+            #[serde(transparent)]
+            pub struct #name(pub String);
+        })?);
+        items.push(parse_item(quote! {
+            impl std::fmt::Display for #name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+        })?);
+        if format_type.path == "Duration" {
+            items.push(parse_item(duration_impl())?);
+        }
+    }
+    Ok(items)
+}
+
+/// Conversion of a Go duration into a `std::time::Duration`
+fn duration_impl() -> TokenStream {
+    quote! {
+        impl Duration {
+            /// The duration as a `std::time::Duration`, unless it is negative or not a valid Go duration
+            pub fn to_std(&self) -> Option<std::time::Duration> {
+                let mut rest = self.0.strip_prefix('+').unwrap_or(&self.0);
+                if rest == "0" {
+                    return Some(std::time::Duration::ZERO);
+                }
+                if rest.is_empty() {
+                    return None;
+                }
+                let mut seconds = 0.0;
+                while !rest.is_empty() {
+                    let unit_start = rest.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+                    let value = rest[..unit_start].parse::<f64>().ok()?;
+                    rest = &rest[unit_start..];
+                    let unit_end = rest
+                        .find(|c: char| c.is_ascii_digit() || c == '.')
+                        .unwrap_or(rest.len());
+                    let unit = match &rest[..unit_end] {
+                        "ns" => 1e-9,
+                        "us" | "µs" | "μs" => 1e-6,
+                        "ms" => 1e-3,
+                        "s" => 1.0,
+                        "m" => 60.0,
+                        "h" => 3600.0,
+                        _ => return None,
+                    };
+                    seconds += value * unit;
+                    rest = &rest[unit_end..];
+                }
+                std::time::Duration::try_from_secs_f64(seconds).ok()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_format_type_parsing() {
+    let uuid = "uuid=uuid::Uuid".parse::<FormatType>().unwrap();
+    assert_eq!(uuid, FormatType::new("uuid", "uuid::Uuid"));
+    assert_eq!(uuid.type_name(), "Uuid");
+    assert_eq!(uuid.import(), Some("uuid::Uuid"));
+    assert!(!uuid.is_newtype());
+    assert!(FormatType::new("uuid", "Uuid").is_newtype());
+
+    assert_eq!(
+        "uuid".parse::<FormatType>().unwrap_err().to_string(),
+        "format type must be <FORMAT>=<PATH> in 'uuid'"
+    );
+    assert_eq!(
+        "=Uuid".parse::<FormatType>().unwrap_err().to_string(),
+        "format cannot be empty in '=Uuid'"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_format_types() {
+    let schema = |yaml: &str| serde_yaml::from_str::<JSONSchemaProps>(yaml).unwrap();
+    let mut format_types = FormatTypes::default();
+    format_types.register(FormatType::new("uuid", "uuid::Uuid"));
+    assert_eq!(format_types.iter().filter(|f| f.format == "uuid").count(), 1);
+
+    let uuid = schema("type: string\nformat: uuid");
+    assert_eq!(format_types.find(&uuid).unwrap().type_name(), "Uuid");
+    let byte = schema("type: string\nformat: byte");
+    assert_eq!(format_types.find(&byte).unwrap().type_name(), "ByteString");
+    let email = schema("type: string\nformat: email");
+    assert_eq!(format_types.find(&email), None);
+
+    let quantity = schema(&format!(
+        "x-kubernetes-int-or-string: true\npattern: '{}'",
+        QUANTITY_PATTERN
+    ));
+    assert_eq!(format_types.find(&quantity).unwrap().type_name(), "Quantity");
+}
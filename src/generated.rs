@@ -0,0 +1,210 @@
+//! Structured result of generating types for a CRD version, before rendering
+use std::collections::HashMap;
+
+use heck::ToUpperCamelCase;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{JSONSchemaProps, ValidationRule};
+use proc_macro2::TokenStream;
+
+use crate::{output::rust_name, render::unwrap_generic, Container, Member};
+
+/// Everything generated for a single version of a CRD
+///
+/// Returned by [`TypeGenerator::generate_types`](crate::TypeGenerator::generate_types), and turned
+/// into Rust source by [`TypeGenerator::render`](crate::TypeGenerator::render).
+#[derive(Clone, Debug)]
+pub struct GeneratedTypes {
+    /// Name of the CRD (e.g. `prometheusrules.monitoring.coreos.com`)
+    pub crd_name: String,
+
+    /// Group of the CRD (e.g. `monitoring.coreos.com`)
+    pub group: String,
+
+    /// Name of the generated CRD version (e.g. `v1`)
+    pub version: String,
+
+    /// Kind of the CRD (e.g. `PrometheusRule`)
+    pub kind: String,
+
+    /// Paths re-exported from the prelude module (e.g. `kube::CustomResource`)
+    pub imports: Vec<String>,
+
+    /// Arguments of the `#[kube(...)]` attributes on the root struct (e.g. `namespaced`)
+    ///
+    /// Empty when kube derives are hidden.
+    pub kube_attrs: Vec<TokenStream>,
+
+    /// All analyzed containers, after renaming, builder and schema default handling
+    ///
+    /// This includes the root container of the schema and elided containers, neither of which are rendered.
+    /// See [`GeneratedTypes::types`] for the rendered containers.
+    pub containers: Vec<Container>,
+
+    /// Names of the containers elided from the output
+    pub elided: Vec<String>,
+
+    /// Warnings raised while analyzing the schema
+    pub warnings: Vec<String>,
+
+    /// The `openAPIV3Schema` of the CRD version
+    pub schema: JSONSchemaProps,
+
+    /// The `openAPIV3Schema` of the CRD version as JSON written like in its input, when it was read
+    pub original_schema: Option<String>,
+}
+
+impl GeneratedTypes {
+    /// The containers that are rendered as Rust types, in output order
+    pub fn types(&self) -> impl Iterator<Item = &Container> {
+        self.containers
+            .iter()
+            .filter(|container| container.level > 0 && !self.elided.contains(&container.name))
+    }
+
+    /// Names of the rendered Rust types
+    pub fn type_names(&self) -> Vec<String> {
+        self.types().map(|container| self.type_name(container)).collect()
+    }
+
+    /// The Rust name of a container
+    ///
+    /// Types nested below the spec drop the `Spec` from their name (e.g. `FooBar` rather than `FooSpecBar`).
+    pub fn type_name(&self, container: &Container) -> String {
+        if container.is_main_container() {
+            container.name.clone()
+        } else {
+            rust_name(&self.kind.to_upper_camel_case(), &container.name)
+        }
+    }
+
+    /// The `x-kubernetes-validations` rules of a member, unless they belong to the container it holds
+    ///
+    /// A member holding a generated container (optionally, or as an explicitly nullable option) has the rules of
+    /// that container.
+    pub fn member_rules<'a>(&self, member: &'a Member) -> &'a [ValidationRule] {
+        let optional = unwrap_generic(&member.type_, "Option<").unwrap_or(&member.type_);
+        let type_ = unwrap_generic(optional, "Option<").unwrap_or(optional);
+        if self.containers.iter().any(|container| container.name == type_) {
+            &[]
+        } else {
+            &member.rules
+        }
+    }
+
+    /// Containers whose Rust name differs from their analyzed name, mapped to their Rust name
+    pub(crate) fn renames(&self) -> HashMap<String, String> {
+        self.containers
+            .iter()
+            .map(|container| (container.name.clone(), self.type_name(container)))
+            .filter(|(name, type_name)| name != type_name)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{crds_from_str, find_crd_version, TypeGenerator};
+
+    #[test]
+    fn generated_types() {
+        let crds = crds_from_str(
+            r#"
+apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: foos.clux.dev
+spec:
+  group: clux.dev
+  names:
+    kind: Foo
+    plural: foos
+  scope: Namespaced
+  versions:
+  - name: v1
+    served: true
+    storage: true
+    schema:
+      openAPIV3Schema:
+        type: object
+        properties:
+          spec:
+            type: object
+            properties:
+              bar:
+                type: object
+                properties:
+                  name:
+                    type: string
+              labels:
+                type: object
+                x-kubernetes-preserve-unknown-fields: true
+          status:
+            type: object
+            properties:
+              fooSpecs:
+                type: object
+                properties:
+                  name:
+                    type: string
+"#,
+        )
+        .unwrap();
+        let crd = &crds[0];
+        let version = find_crd_version(crd, None).unwrap();
+
+        let generator = TypeGenerator::default();
+        let types = generator.generate_types(crd, version).unwrap();
+
+        assert_eq!(types.crd_name, "foos.clux.dev");
+        // only the spec prefix is trimmed, not every occurrence of the spec name
+        assert_eq!(types.type_names(), vec![
+            "FooSpec",
+            "FooBar",
+            "FooStatus",
+            "FooStatusFooSpecs"
+        ]);
+        let kube_attrs = types
+            .kube_attrs
+            .iter()
+            .map(|attr| attr.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(kube_attrs, vec![
+            r#"group = "clux.dev" , version = "v1" , kind = "Foo" , plural = "foos""#,
+            "namespaced",
+            r#"status = "FooStatus""#,
+            r#"schema = "disabled""#,
+        ]);
+        assert!(types.imports.contains(&"kube::CustomResource".to_string()));
+        assert_eq!(types.warnings, vec!["not generating type Labels - using map"]);
+
+        let rendered = generator
+            .render_filtered(&types, |container| !container.is_main_container())
+            .unwrap();
+        assert!(rendered.contains("pub struct FooBar {"));
+        assert!(rendered.contains("pub foo_specs: Option<FooStatusFooSpecs>,"));
+        assert!(!rendered.contains("pub struct FooSpec {"));
+
+        let generator = TypeGenerator::builder().elide(vec!["FooSpecBar".into()]).build();
+        let types = generator.generate_types(crd, version).unwrap();
+        assert_eq!(types.elided, vec!["FooSpecBar"]);
+        assert_eq!(types.type_names(), vec![
+            "FooSpec",
+            "FooStatus",
+            "FooStatusFooSpecs"
+        ]);
+    }
+
+    #[test]
+    fn escapes_preserved_metadata() {
+        let crds = crds_from_str(include_str!("../tests/cmd/generate/crds/settings.clux.dev.yaml")).unwrap();
+        let crd = &crds[0];
+        let version = find_crd_version(crd, None).unwrap();
+
+        let generator = TypeGenerator::builder().preserve_metadata(true).build();
+        let rendered = generator
+            .render(&generator.generate_types(crd, version).unwrap())
+            .unwrap();
+        assert!(rendered.contains(r#"#[kube(annotation("clux.dev/greeting", "say \"hi\" \\o/"))]"#));
+        assert!(rendered.contains(r#""{\"kind\":\"CustomResourceDefinition\"}""#));
+        assert!(rendered.contains(r#"#[kube(label("clux.dev/path", "C:\\settings"))]"#));
+    }
+}
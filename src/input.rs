@@ -0,0 +1,217 @@
+//! Reading CustomResourceDefinitions from files, directories, and multi-document input
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::Context;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use serde::Deserialize;
+
+const CRD_KIND: &str = "CustomResourceDefinition";
+
+/// Extensions of files that are read when a directory is given
+const EXTENSIONS: [&str; 3] = ["yaml", "yml", "json"];
+
+/// The `openAPIV3Schema` of CRD versions, as JSON written like in the input they were read from
+///
+/// Reading a [`CustomResourceDefinition`] turns the integer bounds of its schema into floats, sorts its keys and
+/// drops keywords it does not know, so [`SchemaMode::Preserved`](crate::SchemaMode::Preserved) embeds these instead.
+#[derive(Clone, Debug, Default)]
+pub struct OriginalSchemas(BTreeMap<(String, String), String>);
+
+impl OriginalSchemas {
+    /// The schema of the version `version` of the CRD named `crd`, if it was read
+    pub fn get(&self, crd: &str, version: &str) -> Option<&str> {
+        self.0
+            .get(&(crd.to_string(), version.to_string()))
+            .map(String::as_str)
+    }
+
+    /// Add the schemas read from other input
+    pub fn extend(&mut self, other: OriginalSchemas) {
+        self.0.extend(other.0);
+    }
+}
+
+/// Read all CRDs from YAML or JSON data
+///
+/// The data can contain multiple YAML documents, as well as `List` objects with CRDs in their `items`.
+/// Documents that are not CRDs are skipped.
+pub fn crds_from_str(data: &str) -> anyhow::Result<Vec<CustomResourceDefinition>> {
+    crd_documents(data)?
+        .into_iter()
+        .map(|value| serde_yaml::from_value(value).context("Failed to parse CustomResourceDefinition"))
+        .collect()
+}
+
+/// Read all CRDs from a file, or from all yaml and json files in a directory
+///
+/// See [`crds_from_str`] for the supported file contents.
+pub fn crds_from_path(path: impl AsRef<Path>) -> anyhow::Result<Vec<CustomResourceDefinition>> {
+    read_path(path.as_ref(), crds_from_str)
+}
+
+/// Read the original schemas of all CRDs in YAML or JSON data
+///
+/// See [`crds_from_str`] for the supported data.
+pub fn schemas_from_str(data: &str) -> anyhow::Result<OriginalSchemas> {
+    let mut schemas = OriginalSchemas::default();
+    for crd in crd_documents(data)? {
+        let name = crd["metadata"]["name"].as_str().unwrap_or_default();
+        for version in crd["spec"]["versions"].as_sequence().into_iter().flatten() {
+            let (Some(version_name), Some(schema)) = (
+                version["name"].as_str(),
+                version
+                    .get("schema")
+                    .and_then(|schema| schema.get("openAPIV3Schema")),
+            ) else {
+                continue;
+            };
+            let schema = serde_json::to_string(schema).context("Failed to read openAPIV3Schema")?;
+            schemas
+                .0
+                .insert((name.to_string(), version_name.to_string()), schema);
+        }
+    }
+    Ok(schemas)
+}
+
+/// Read the original schemas of all CRDs in a file, or in all yaml and json files in a directory
+///
+/// See [`crds_from_str`] for the supported file contents.
+pub fn schemas_from_path(path: impl AsRef<Path>) -> anyhow::Result<OriginalSchemas> {
+    let mut schemas = OriginalSchemas::default();
+    for file_schemas in read_path(path.as_ref(), |data| Ok(vec![schemas_from_str(data)?]))? {
+        schemas.extend(file_schemas);
+    }
+    Ok(schemas)
+}
+
+fn read_path<T>(path: &Path, parse: fn(&str) -> anyhow::Result<Vec<T>>) -> anyhow::Result<Vec<T>> {
+    if !path.is_dir() {
+        let data =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        return parse(&data).with_context(|| format!("Failed to parse {}", path.display()));
+    }
+
+    let mut files = std::fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory {}", path.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.retain(|file| {
+        file.is_file()
+            && file
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| EXTENSIONS.contains(&ext))
+    });
+    // directory order is not stable across platforms
+    files.sort();
+
+    let mut parsed = vec![];
+    for file in files {
+        parsed.extend(read_path(&file, parse)?);
+    }
+    Ok(parsed)
+}
+
+/// The documents of all CRDs in YAML or JSON data
+fn crd_documents(data: &str) -> anyhow::Result<Vec<serde_yaml::Value>> {
+    let mut crds = vec![];
+    for document in serde_yaml::Deserializer::from_str(data) {
+        let value = serde_yaml::Value::deserialize(document)?;
+        collect_crds(value, &mut crds);
+    }
+    Ok(crds)
+}
+
+fn collect_crds(value: serde_yaml::Value, crds: &mut Vec<serde_yaml::Value>) {
+    if value.is_null() {
+        return; // empty document
+    }
+    match value.get("kind").and_then(|kind| kind.as_str()) {
+        Some(kind) if kind.ends_with("List") => {
+            let items = value.get("items").and_then(|items| items.as_sequence());
+            for item in items.into_iter().flatten() {
+                collect_crds(item.clone(), crds);
+            }
+        }
+        Some(kind) if kind != CRD_KIND => {
+            log::debug!("skipping non-crd document of kind {}", kind);
+        }
+        _ => crds.push(value),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crds_from_str, schemas_from_str};
+
+    fn crd(kind: &str) -> String {
+        format!(
+            r#"
+apiVersion: apiextensions.k8s.io/v1
+kind: CustomResourceDefinition
+metadata:
+  name: {plural}.example.com
+spec:
+  group: example.com
+  names:
+    kind: {kind}
+    plural: {plural}
+  scope: Namespaced
+  versions: []
+"#,
+            plural = kind.to_lowercase()
+        )
+    }
+
+    #[test]
+    fn multi_document_yaml() {
+        let data = format!(
+            "---\n{}\n---\napiVersion: v1\nkind: Namespace\nmetadata:\n  name: skipped\n---\n{}\n---\n",
+            crd("Foo"),
+            crd("Bar")
+        );
+        let crds = crds_from_str(&data).unwrap();
+        assert_eq!(crds.len(), 2);
+        assert_eq!(crds[0].spec.names.kind, "Foo");
+        assert_eq!(crds[1].spec.names.kind, "Bar");
+    }
+
+    #[test]
+    fn json_list() {
+        let foo: serde_yaml::Value = serde_yaml::from_str(&crd("Foo")).unwrap();
+        let list = serde_json::json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "CustomResourceDefinitionList",
+            "items": [foo, {"apiVersion": "v1", "kind": "ConfigMap"}],
+        });
+        let crds = crds_from_str(&list.to_string()).unwrap();
+        assert_eq!(crds.len(), 1);
+        assert_eq!(crds[0].spec.names.kind, "Foo");
+    }
+
+    #[test]
+    fn original_schemas() {
+        let data = format!(
+            "{}\n---\n{}",
+            crd("Foo").replace(
+                "versions: []",
+                "versions:\n    - name: v1\n      schema:\n        openAPIV3Schema:\n          type: object\n          maximum: 10"
+            ),
+            crd("Bar")
+        );
+        let schemas = schemas_from_str(&data).unwrap();
+        assert_eq!(
+            schemas.get("foo.example.com", "v1"),
+            Some(r#"{"type":"object","maximum":10}"#)
+        );
+        assert_eq!(schemas.get("foo.example.com", "v2"), None);
+        assert_eq!(schemas.get("bar.example.com", "v1"), None);
+    }
+
+    #[test]
+    fn invalid_crd_is_an_error() {
+        let data = "apiVersion: apiextensions.k8s.io/v1\nkind: CustomResourceDefinition\nspec: 1\n";
+        assert!(crds_from_str(data).is_err());
+    }
+}
@@ -0,0 +1,562 @@
+//! Registry of well-known Kubernetes types, used in place of generated structs for schemas matching them
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+    JSONSchemaProps, JSONSchemaPropsOrArray,
+};
+
+/// How closely the properties of a schema must match those of a [`KnownType`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Tolerance {
+    /// The schema has exactly the properties of the type
+    #[default]
+    Exact,
+
+    /// The schema may omit properties of the type, as older or stripped-down CRDs do
+    Subset,
+
+    /// The schema may have properties unknown to the type, which are dropped when deserializing
+    Superset,
+
+    /// The schema may both omit properties of the type and have properties unknown to it
+    Loose,
+}
+
+impl Tolerance {
+    fn allows_missing(self) -> bool {
+        matches!(self, Self::Subset | Self::Loose)
+    }
+
+    fn allows_unknown(self) -> bool {
+        matches!(self, Self::Superset | Self::Loose)
+    }
+}
+
+/// A Rust type that is used for object schemas matching its shape, rather than generating a struct
+///
+/// A schema matches when it has all the `required` properties, its properties have the same `type` as those of
+/// the known type, and its set of properties matches that of the known type within the `tolerance`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KnownType {
+    /// Path of the type, re-exported from the prelude (e.g. `k8s_openapi::api::core::v1::Toleration`)
+    pub path: String,
+
+    /// Properties of the type along with their schema `type` (e.g. `("tolerationSeconds", "integer")`)
+    pub properties: Vec<(String, String)>,
+
+    /// Properties a schema must have to match
+    ///
+    /// Nested properties are given as dotted paths, which pass through array items (e.g. `spec.containers`).
+    pub required: Vec<String>,
+
+    /// How closely the properties of a schema must match
+    pub tolerance: Tolerance,
+
+    /// Keys under which the type is matched (e.g. `conditions`), or any key if empty
+    pub keys: Vec<String>,
+
+    /// Only match schemas with `x-kubernetes-map-type: atomic`, which Kubernetes sets for the type
+    ///
+    /// This tells apart common types with generic shapes, such as `LocalObjectReference`.
+    pub atomic: bool,
+}
+
+impl KnownType {
+    /// A type matching schemas with exactly these properties and types
+    pub fn new<'a>(
+        path: impl Into<String>,
+        properties: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            properties: properties
+                .into_iter()
+                .map(|(name, type_)| (name.to_string(), type_.to_string()))
+                .collect(),
+            required: vec![],
+            tolerance: Tolerance::Exact,
+            keys: vec![],
+            atomic: false,
+        }
+    }
+
+    /// Require schemas to have these properties
+    pub fn required<'a>(mut self, required: impl IntoIterator<Item = &'a str>) -> Self {
+        self.required.extend(required.into_iter().map(String::from));
+        self
+    }
+
+    /// Match schemas within this tolerance
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Only match schemas under this key
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+
+    /// Only match schemas marked as atomic
+    pub fn atomic(mut self) -> Self {
+        self.atomic = true;
+        self
+    }
+
+    /// Name of the type in generated code; the last segment of its path
+    pub fn name(&self) -> &str {
+        self.path.rsplit("::").next().unwrap_or(&self.path)
+    }
+
+    /// Whether the object schema of the property `key` matches this type
+    pub fn matches(&self, key: &str, schema: &JSONSchemaProps) -> bool {
+        if !self.keys.is_empty() && !self.keys.iter().any(|k| k == key) {
+            return false;
+        }
+        if self.atomic && schema.x_kubernetes_map_type.as_deref() != Some("atomic") {
+            return false;
+        }
+        let Some(props) = schema.properties.as_ref().filter(|props| !props.is_empty()) else {
+            return false;
+        };
+        if !self.required.iter().all(|path| has_property(schema, path)) {
+            return false;
+        }
+
+        for (name, prop) in props {
+            match self.properties.iter().find(|(known, _)| known == name) {
+                Some((_, type_)) if prop.type_.as_ref().is_some_and(|t| t != type_) => return false,
+                Some(_) => {}
+                None if self.tolerance.allows_unknown() => {}
+                None => return false,
+            }
+        }
+        self.tolerance.allows_missing() || self.properties.iter().all(|(name, _)| props.contains_key(name))
+    }
+}
+
+/// Whether a schema has the property at a dotted path, looking through array items
+fn has_property(schema: &JSONSchemaProps, path: &str) -> bool {
+    let mut current = schema;
+    for name in path.split('.') {
+        while let Some(JSONSchemaPropsOrArray::Schema(items)) = &current.items {
+            current = items;
+        }
+        match current.properties.as_ref().and_then(|props| props.get(name)) {
+            Some(prop) => current = prop,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// An ordered registry of [`KnownType`]s, of which the first one matching a schema is used
+///
+/// The default registry holds the `Condition` and `ObjectReference` of `k8s_openapi`, [`KnownTypes::common`] adds
+/// other common types of `k8s_openapi`, and either can be extended with [`KnownTypes::register`]:
+///
+/// ```
+/// use kopium::{KnownType, KnownTypes, Tolerance};
+///
+/// let mut known_types = KnownTypes::common();
+/// known_types.register(
+///     KnownType::new("k8s_openapi::api::core::v1::ContainerPort", [
+///         ("containerPort", "integer"),
+///         ("hostIP", "string"),
+///         ("hostPort", "integer"),
+///         ("name", "string"),
+///         ("protocol", "string"),
+///     ])
+///     .required(["containerPort", "protocol"])
+///     .tolerance(Tolerance::Subset),
+/// );
+/// let generator = kopium::TypeGenerator::builder().known_types(known_types).build();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct KnownTypes(Vec<KnownType>);
+
+impl Default for KnownTypes {
+    fn default() -> Self {
+        let meta = |name: &str| format!("k8s_openapi::apimachinery::pkg::apis::meta::v1::{}", name);
+        let core = |name: &str| format!("k8s_openapi::api::core::v1::{}", name);
+
+        Self(vec![
+            KnownType::new(meta("Condition"), [
+                ("lastTransitionTime", "string"),
+                ("message", "string"),
+                ("observedGeneration", "integer"),
+                ("reason", "string"),
+                ("status", "string"),
+                ("type", "string"),
+            ])
+            .required(["lastTransitionTime", "message", "reason", "status", "type"])
+            .tolerance(Tolerance::Loose)
+            .key("conditions"),
+            KnownType::new(core("ObjectReference"), [
+                ("apiVersion", "string"),
+                ("fieldPath", "string"),
+                ("kind", "string"),
+                ("name", "string"),
+                ("namespace", "string"),
+                ("resourceVersion", "string"),
+                ("uid", "string"),
+            ]),
+        ])
+    }
+}
+
+impl KnownTypes {
+    /// The default types, along with common types of `k8s_openapi` (e.g. `LabelSelector`, `Toleration`,
+    /// `Container` or `Volume`)
+    pub fn common() -> Self {
+        let meta = |name: &str| format!("k8s_openapi::apimachinery::pkg::apis::meta::v1::{}", name);
+        let core = |name: &str| format!("k8s_openapi::api::core::v1::{}", name);
+        let key_selector = [("key", "string"), ("name", "string"), ("optional", "boolean")];
+
+        let mut known_types = Self::default();
+        known_types.0.extend([
+            KnownType::new(core("LocalObjectReference"), [("name", "string")]).atomic(),
+            // shaped like a SecretKeySelector, so only told apart by the key holding it
+            KnownType::new(core("ConfigMapKeySelector"), key_selector)
+                .required(["key", "name"])
+                .tolerance(Tolerance::Subset)
+                .atomic()
+                .key("configMap")
+                .key("configMapKeyRef"),
+            KnownType::new(core("SecretKeySelector"), key_selector)
+                .required(["key", "name"])
+                .tolerance(Tolerance::Subset)
+                .atomic(),
+            KnownType::new(meta("LabelSelector"), [
+                ("matchExpressions", "array"),
+                ("matchLabels", "object"),
+            ])
+            .tolerance(Tolerance::Subset)
+            .atomic(),
+            // exactly, as the limits and requests of a persistent volume claim are a VolumeResourceRequirements
+            KnownType::new(core("ResourceRequirements"), [
+                ("claims", "array"),
+                ("limits", "object"),
+                ("requests", "object"),
+            ]),
+            KnownType::new(core("Toleration"), [
+                ("effect", "string"),
+                ("key", "string"),
+                ("operator", "string"),
+                ("tolerationSeconds", "integer"),
+                ("value", "string"),
+            ])
+            .required(["effect", "key", "operator"])
+            .tolerance(Tolerance::Subset),
+            KnownType::new(core("Affinity"), [
+                ("nodeAffinity", "object"),
+                ("podAffinity", "object"),
+                ("podAntiAffinity", "object"),
+            ])
+            .required(["nodeAffinity", "podAffinity", "podAntiAffinity"])
+            .tolerance(Tolerance::Subset),
+            KnownType::new(core("EnvVar"), [
+                ("name", "string"),
+                ("value", "string"),
+                ("valueFrom", "object"),
+            ])
+            .required(["name", "valueFrom"])
+            .tolerance(Tolerance::Subset),
+            KnownType::new(core("Container"), [
+                ("args", "array"),
+                ("command", "array"),
+                ("env", "array"),
+                ("envFrom", "array"),
+                ("image", "string"),
+                ("imagePullPolicy", "string"),
+                ("lifecycle", "object"),
+                ("livenessProbe", "object"),
+                ("name", "string"),
+                ("ports", "array"),
+                ("readinessProbe", "object"),
+                ("resizePolicy", "array"),
+                ("resources", "object"),
+                ("restartPolicy", "string"),
+                ("restartPolicyRules", "array"),
+                ("securityContext", "object"),
+                ("startupProbe", "object"),
+                ("stdin", "boolean"),
+                ("stdinOnce", "boolean"),
+                ("terminationMessagePath", "string"),
+                ("terminationMessagePolicy", "string"),
+                ("tty", "boolean"),
+                ("volumeDevices", "array"),
+                ("volumeMounts", "array"),
+                ("workingDir", "string"),
+            ])
+            .required(["name", "image", "imagePullPolicy"])
+            .tolerance(Tolerance::Subset),
+            KnownType::new(core("Volume"), [
+                ("awsElasticBlockStore", "object"),
+                ("azureDisk", "object"),
+                ("azureFile", "object"),
+                ("cephfs", "object"),
+                ("cinder", "object"),
+                ("configMap", "object"),
+                ("csi", "object"),
+                ("downwardAPI", "object"),
+                ("emptyDir", "object"),
+                ("ephemeral", "object"),
+                ("fc", "object"),
+                ("flexVolume", "object"),
+                ("flocker", "object"),
+                ("gcePersistentDisk", "object"),
+                ("gitRepo", "object"),
+                ("glusterfs", "object"),
+                ("hostPath", "object"),
+                ("image", "object"),
+                ("iscsi", "object"),
+                ("name", "string"),
+                ("nfs", "object"),
+                ("persistentVolumeClaim", "object"),
+                ("photonPersistentDisk", "object"),
+                ("portworxVolume", "object"),
+                ("projected", "object"),
+                ("quobyte", "object"),
+                ("rbd", "object"),
+                ("scaleIO", "object"),
+                ("secret", "object"),
+                ("storageos", "object"),
+                ("vsphereVolume", "object"),
+            ])
+            .required(["name", "configMap", "emptyDir", "secret"])
+            .tolerance(Tolerance::Subset),
+            KnownType::new(core("PodTemplateSpec"), [
+                ("metadata", "object"),
+                ("spec", "object"),
+            ])
+            .required(["spec.containers"])
+            .tolerance(Tolerance::Subset),
+        ]);
+        known_types
+    }
+
+    /// A registry without any types
+    pub fn empty() -> Self {
+        Self(vec![])
+    }
+
+    /// Add a type, matched after all types registered before it
+    pub fn register(&mut self, known: KnownType) {
+        self.0.push(known);
+    }
+
+    /// Remove the types with this name (e.g. `Toleration`)
+    pub fn remove(&mut self, name: &str) {
+        self.0.retain(|known| known.name() != name);
+    }
+
+    /// The first type matching the object schema of the property `key`
+    pub fn find(&self, key: &str, schema: &JSONSchemaProps) -> Option<&KnownType> {
+        self.0.iter().find(|known| known.matches(key, schema))
+    }
+
+    /// The registered types, in the order they are matched
+    pub fn iter(&self) -> impl Iterator<Item = &KnownType> {
+        self.0.iter()
+    }
+
+    pub(crate) fn retain(&mut self, keep: impl FnMut(&KnownType) -> bool) {
+        self.0.retain(keep);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use k8s_openapi::{
+        api::core::v1::{
+            Affinity, ConfigMapKeySelector, Container, EnvVar, LocalObjectReference, ObjectReference,
+            PodTemplateSpec, ResourceRequirements, SecretKeySelector, Toleration, Volume,
+        },
+        apiextensions_apiserver::pkg::apis::apiextensions::v1::JSONSchemaProps,
+        apimachinery::pkg::apis::meta::v1::{Condition, LabelSelector},
+    };
+
+    use super::{KnownType, KnownTypes, Tolerance};
+
+    fn schema(yaml: &str) -> JSONSchemaProps {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    /// The top level properties and types of the schemars schema of a k8s_openapi type
+    fn properties_of<T: schemars::JsonSchema>() -> Vec<(String, String)> {
+        let schema = schemars::SchemaGenerator::default().into_root_schema_for::<T>();
+        let mut properties = schema
+            .get("properties")
+            .and_then(|props| props.as_object())
+            .unwrap()
+            .iter()
+            .map(|(name, prop)| {
+                // nested types are references to definitions
+                let prop = match prop.get("$ref").and_then(|r| r.as_str()) {
+                    Some(reference) => schema.pointer(reference.trim_start_matches('#')).unwrap(),
+                    None => prop,
+                };
+                (
+                    name.clone(),
+                    prop["type"].as_str().unwrap_or("object").to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        properties.sort();
+        properties
+    }
+
+    #[test]
+    fn builtin_types_match_k8s_openapi() {
+        let known_types = KnownTypes::common();
+        let expected = [
+            ("Condition", properties_of::<Condition>()),
+            ("ObjectReference", properties_of::<ObjectReference>()),
+            ("LocalObjectReference", properties_of::<LocalObjectReference>()),
+            ("ConfigMapKeySelector", properties_of::<ConfigMapKeySelector>()),
+            ("SecretKeySelector", properties_of::<SecretKeySelector>()),
+            ("LabelSelector", properties_of::<LabelSelector>()),
+            ("ResourceRequirements", properties_of::<ResourceRequirements>()),
+            ("Toleration", properties_of::<Toleration>()),
+            ("Affinity", properties_of::<Affinity>()),
+            ("EnvVar", properties_of::<EnvVar>()),
+            ("Container", properties_of::<Container>()),
+            ("Volume", properties_of::<Volume>()),
+            ("PodTemplateSpec", properties_of::<PodTemplateSpec>()),
+        ];
+        assert_eq!(known_types.iter().count(), expected.len());
+        assert_eq!(KnownTypes::default().iter().count(), 2);
+        for (known, (name, properties)) in known_types.iter().zip(expected) {
+            assert_eq!(known.name(), name);
+            assert_eq!(known.properties, properties, "properties of {}", name);
+        }
+    }
+
+    #[test]
+    fn tolerance() {
+        let toleration = schema(
+            r#"
+            type: object
+            properties:
+              effect:
+                type: string
+              key:
+                type: string
+              operator:
+                type: string
+            "#,
+        );
+        let known_types = KnownTypes::common();
+        assert_eq!(
+            known_types.find("tolerations", &toleration).map(KnownType::name),
+            Some("Toleration")
+        );
+
+        let exact = KnownType::new("k8s_openapi::api::core::v1::Toleration", [
+            ("effect", "string"),
+            ("key", "string"),
+            ("operator", "string"),
+            ("tolerationSeconds", "integer"),
+            ("value", "string"),
+        ]);
+        assert!(!exact.matches("tolerations", &toleration));
+        assert!(exact
+            .clone()
+            .tolerance(Tolerance::Subset)
+            .matches("tolerations", &toleration));
+
+        let mut extended = toleration.clone();
+        let props = extended.properties.as_mut().unwrap();
+        props.insert("priority".into(), schema("type: integer"));
+        assert!(known_types.find("tolerations", &extended).is_none());
+        assert!(exact
+            .clone()
+            .tolerance(Tolerance::Loose)
+            .matches("tolerations", &extended));
+
+        // properties must have the same types
+        let mut mistyped = toleration.clone();
+        let props = mistyped.properties.as_mut().unwrap();
+        props.insert("tolerationSeconds".into(), schema("type: string"));
+        assert!(known_types.find("tolerations", &mistyped).is_none());
+    }
+
+    #[test]
+    fn nested_required_properties() {
+        let template = schema(
+            r#"
+            type: object
+            properties:
+              metadata:
+                type: object
+              spec:
+                type: object
+                properties:
+                  containers:
+                    type: array
+                    items:
+                      type: object
+                      properties:
+                        name:
+                          type: string
+            "#,
+        );
+        let known_types = KnownTypes::common();
+        assert_eq!(
+            known_types.find("template", &template).map(KnownType::name),
+            Some("PodTemplateSpec")
+        );
+
+        let mut not_a_pod = template.clone();
+        let props = not_a_pod.properties.as_mut().unwrap();
+        props.insert("spec".into(), schema("type: object"));
+        assert!(known_types.find("template", &not_a_pod).is_none());
+    }
+
+    #[test]
+    fn key_selectors_by_key() {
+        let selector = schema(
+            r#"
+            type: object
+            properties:
+              key:
+                type: string
+              name:
+                type: string
+            x-kubernetes-map-type: atomic
+            "#,
+        );
+        let mut known_types = KnownTypes::common();
+        let name = |key| known_types.find(key, &selector).map(KnownType::name);
+        assert_eq!(name("configMap"), Some("ConfigMapKeySelector"));
+        assert_eq!(name("secret"), Some("SecretKeySelector"));
+
+        known_types.remove("SecretKeySelector");
+        assert!(known_types.find("secret", &selector).is_none());
+    }
+
+    #[test]
+    fn claim_resources_are_not_container_resources() {
+        let claim_resources = schema(
+            r#"
+            type: object
+            properties:
+              limits:
+                type: object
+              requests:
+                type: object
+            "#,
+        );
+        assert!(KnownTypes::common().find("resources", &claim_resources).is_none());
+
+        let mut container_resources = claim_resources.clone();
+        let props = container_resources.properties.as_mut().unwrap();
+        props.insert("claims".into(), schema("type: array"));
+        assert_eq!(
+            KnownTypes::common()
+                .find("resources", &container_resources)
+                .map(KnownType::name),
+            Some("ResourceRequirements")
+        );
+    }
+}
@@ -5,18 +5,47 @@ use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
     CustomResourceDefinition, CustomResourceDefinitionVersion,
 };
 use kube::{core::Version, ResourceExt};
+use proc_macro2::TokenStream;
+use quote::quote;
 
 mod analyzer;
+pub mod build;
 
 mod derive;
+mod files;
+mod formats;
+mod generated;
+mod input;
+mod known;
+mod lists;
+mod naming;
 mod output;
+mod overrides;
+mod renames;
+mod render;
+mod schema;
+mod validate;
 
 pub use self::{
     analyzer::{analyze, Config},
     derive::Derive,
-    output::{format_docstr, format_selectable, Container, MapType, Member, Output},
+    files::{write_if_changed, GeneratedFile, Layout},
+    formats::{FormatType, FormatTypes},
+    generated::GeneratedTypes,
+    input::{crds_from_path, crds_from_str, schemas_from_path, schemas_from_str, OriginalSchemas},
+    known::{KnownType, KnownTypes, Tolerance},
+    naming::NamingStrategy,
+    output::{
+        format_docstr, format_selectable, Constraints, Container, DateTimeBackend, EmbeddedResources,
+        ListType, MapType, Member, Output,
+    },
+    overrides::TypeOverride,
+    renames::Rename,
 };
 
+/// The version of kopium, as recorded in generated files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Supported values for `kube`'s [`schema`] attribute.
 ///
 /// [schema]: https://docs.rs/kube/latest/kube/derive.CustomResource.html#kubeschema--mode
@@ -41,6 +70,13 @@ pub enum SchemaMode {
     /// Instruct `kube` to automatically derive a `JsonSchema` implementation for `kopium` generated type(s).
     Derived,
 
+    /// Embed the original schema of the CRD, and implement `JsonSchema` with it for the custom resource and its spec and status.
+    ///
+    /// `kube` is instructed to use these manual implementations, so the CRD generated from the types carries the
+    /// upstream schema, including its descriptions, validations, defaults and `x-kubernetes-*` extensions.
+    /// The schema is embedded as it was written in the input when it is found in [`TypeGenerator::original_schemas`].
+    Preserved,
+
     /// Instruct `kube` to skip deriving a `JsonSchema` implementation for `kopium` generated type(s) entirely.
     ///
     /// **NOTE**: the resulting CRD cannot be applied to a cluster without manual fiddling to add an OpenAPI schema.
@@ -51,7 +87,7 @@ pub enum SchemaMode {
 #[cfg(feature = "cli")]
 impl clap::ValueEnum for SchemaMode {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Manual, Self::Derived, Self::Disabled]
+        &[Self::Manual, Self::Derived, Self::Preserved, Self::Disabled]
     }
 
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
@@ -83,6 +119,23 @@ impl clap::ValueEnum for SchemaMode {
                 }
             }
         }
+
+        /// Use the type at `path` for `target`, a JSON path (e.g. `spec.template`)
+        /// or the name of a generated type (e.g. `FooConfig`)
+        pub fn type_override(&mut self, target: impl Into<String>, path: impl Into<String>) {
+            self.type_overrides.push(TypeOverride::new(target, path));
+        }
+
+        /// Rename `target`, a generated type (e.g. `FooConfig`), field (e.g. `FooConfig.tlsConfig`)
+        /// or enum variant (e.g. `FooMode::Http`), to `to`
+        pub fn rename(&mut self, target: impl Into<String>, to: impl Into<String>) {
+            self.renames.push(Rename::new(target, to));
+        }
+
+        /// Use the type at `path` for strings with `format` (e.g. `uuid`)
+        pub fn format_type(&mut self, format: impl Into<String>, path: impl Into<String>) {
+            self.format_overrides.push(FormatType::new(format, path));
+        }
     )
 )]
 pub struct TypeGenerator {
@@ -90,6 +143,13 @@ pub struct TypeGenerator {
     #[cfg_attr(feature = "cli", arg(long))]
     pub api_version: Option<String>,
 
+    /// Generate all served CRD versions, each into its own module
+    ///
+    /// Every module is named after its version (e.g. `pub mod v1beta1`) and carries its own prelude.
+    /// Versions marked as deprecated in the CRD get a `#[deprecated]` attribute on their module.
+    #[cfg_attr(feature = "cli", arg(long, conflicts_with = "api_version"))]
+    pub all_versions: bool,
+
     /// Do not emit prelude(s)
     #[cfg_attr(feature = "cli", arg(long))]
     pub hide_prelude: bool,
@@ -104,6 +164,28 @@ pub struct TypeGenerator {
     #[cfg_attr(feature = "cli", arg(short = 'd', long = "docs"))]
     pub emit_docs: bool,
 
+    /// Emit schema defaults through serde default functions and `Default` impls
+    ///
+    /// Members with a `default` in the schema get a `#[serde(default = "...")]` function returning that
+    /// value, and containers with such members get a manual `Default` impl instead of a derived one.
+    #[cfg_attr(feature = "cli", arg(long = "defaults"))]
+    pub emit_defaults: bool,
+
+    /// Emit a `Validate` impl checking the value constraints of the schema
+    ///
+    /// Checks `minimum`, `maximum`, `multipleOf`, `pattern`, `minLength`, `maxLength`, `minItems`,
+    /// `maxItems`, `uniqueItems`, `minProperties` and `maxProperties`, and reports the JSON path of every
+    /// violation. The generated code uses the `regex` crate when the schema has patterns.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub validate: bool,
+
+    /// Emit a `ValidateCel` impl evaluating the `x-kubernetes-validations` rules of the schema
+    ///
+    /// Rules are evaluated in-process with the `cel` crate, with `self` bound to the serialized value, and
+    /// `oldSelf` to the serialized old value in transition rules. Transition rules are skipped without an old value.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub validate_cel: bool,
+
     /// Preserve top-level annotations and labels from source CRD
     #[cfg_attr(feature = "cli", arg(short = 'm', long = "preserve-metadata"))]
     pub preserve_metadata: bool,
@@ -125,12 +207,23 @@ pub struct TypeGenerator {
     /// --schema=derived implies `--derive JsonSchema`. The resulting schema will compile without external user action.
     /// The crd via `CustomResourceExt::crd()` can be applied into Kubernetes directly.
     ///
+    /// --schema=preserved embeds the original schema and implements `JsonSchema` with it for the custom resource,
+    /// its spec and its status. The crd via `CustomResourceExt::crd()` then carries the upstream schema, and needs
+    /// `schemars` and `serde_json` to compile.
+    ///
     /// See: https://docs.rs/kube/latest/kube/derive.CustomResource.html#kubeschema--mode and
     /// https://docs.rs/kube/latest/kube/trait.CustomResourceExt.html#tymethod.crd
     #[builder(default)]
     #[cfg_attr(feature = "cli", arg(long = "schema", default_value_t))]
     pub schema_mode: SchemaMode,
 
+    /// Schemas embedded by `--schema=preserved` as they were written in the input
+    ///
+    /// The schemas of CRDs missing here are embedded as read into `CustomResourceDefinition`,
+    /// with integer bounds written as floats. See [`OriginalSchemas`].
+    #[cfg_attr(feature = "cli", arg(skip))]
+    pub original_schemas: OriginalSchemas,
+
     /// Derive these additional traits on generated objects
     ///
     /// There are three different ways of specifying traits to derive:
@@ -156,6 +249,50 @@ pub struct TypeGenerator {
     #[builder(via_mutators(init = Default::default()))]
     pub derive_traits: Vec<Derive>,
 
+    /// Use a type of your own for a property, rather than generating one
+    ///
+    /// Takes `<TARGET>=<PATH>`, where the target is the JSON path of a property (e.g. `spec.template`) or the
+    /// name of a generated type (e.g. `FooConfig`), and the path is the type to use (e.g. `my_crate::Config`).
+    /// No types are generated for the property or anything below it, and the type is imported through the prelude.
+    /// Scalar properties are overridden too, and arrays keep their type, with their items overridden. Can be given
+    /// multiple times.
+    #[cfg_attr(feature = "cli", arg(
+        long = "type-override",
+        value_name = "TARGET=PATH",
+        value_parser = TypeOverride::from_str,
+        action = clap::ArgAction::Append,
+    ))]
+    #[builder(via_mutators(init = Default::default()))]
+    pub type_overrides: Vec<TypeOverride>,
+
+    /// How to name generated types
+    ///
+    /// Names are always prefixed by the kind. Names that would be taken by another type fall back to the `full` name.
+    #[cfg_attr(feature = "cli", arg(long, value_enum, default_value_t))]
+    pub naming: NamingStrategy,
+
+    /// Generate a single type for structurally identical objects
+    ///
+    /// Objects with the same properties under different paths (e.g. the TLS configs of several endpoints) share
+    /// one type, named after the words their generated names start and end with (e.g. `FooTlsConfig`), or else
+    /// the shortest of those names. The type only keeps a description when all merged objects share it.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub dedup_types: bool,
+
+    /// Rename a generated type, field or enum variant
+    ///
+    /// Takes `<TARGET>=<NAME>`, where the target is a type (e.g. `FooConfig`), a field (e.g. `FooConfig.tlsConfig`)
+    /// or an enum variant (e.g. `FooMode::Http`). Fields and variants are given with the generated name of their type,
+    /// by their Rust or schema name, and keep serializing as their schema name. Can be given multiple times.
+    #[cfg_attr(feature = "cli", arg(
+        long = "rename",
+        value_name = "TARGET=NAME",
+        value_parser = Rename::from_str,
+        action = clap::ArgAction::Append,
+    ))]
+    #[builder(via_mutators(init = Default::default()))]
+    pub renames: Vec<Rename>,
+
     /// Elide the following containers from the output
     ///
     /// This allows manual customization of structs from the output without having to remove it from
@@ -170,6 +307,13 @@ pub struct TypeGenerator {
     #[cfg_attr(feature = "cli", arg(long))]
     pub relaxed: bool,
 
+    /// Tell explicit nulls apart from absent values for optional nullable fields
+    ///
+    /// These fields become an `Option<Option<T>>`, which is `Some(None)` for an explicit `null`, as merge patches
+    /// use to unset a field. Required nullable fields are always an `Option<T>`.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub explicit_null: bool,
+
     /// Disable standardized Condition API
     ///
     /// By default, kopium detects Condition objects and uses a standard
@@ -184,11 +328,79 @@ pub struct TypeGenerator {
     #[cfg_attr(feature = "cli", arg(long))]
     pub no_object_reference: bool,
 
+    /// Use common types from k8s_openapi for schemas shaped like them
+    ///
+    /// Detects schemas shaped like common Kubernetes types (such as `LabelSelector`, `Toleration`, `Container` or
+    /// `Volume`) and uses the k8s_openapi type instead of generating a custom definition.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub well_known_types: bool,
+
+    /// Do not use this well-known type from k8s_openapi
+    ///
+    /// Takes the name of the type (e.g. `Toleration`), and can be given multiple times.
+    #[cfg_attr(feature = "cli", arg(long = "no-known-type", value_name = "TYPE"))]
+    pub no_known_types: Vec<String>,
+
+    /// Well-known types used in place of generated types for schemas matching them
+    ///
+    /// Defaults to the `Condition` and `ObjectReference` of k8s_openapi. See [`KnownTypes`] to register more.
+    #[cfg_attr(feature = "cli", arg(skip))]
+    pub known_types: KnownTypes,
+
+    /// Use types for strings with well-known formats
+    ///
+    /// Strings with a `byte`, `ipv4` or `ipv6` format, and Kubernetes quantities, use types from k8s_openapi and std.
+    /// Strings with a `cidr`, `duration`, `hostname` or `uuid` format use generated newtypes of a string.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub string_formats: bool,
+
+    /// Use a type for strings with a format
+    ///
+    /// Takes `<FORMAT>=<PATH>` (e.g. `uuid=uuid::Uuid`), where the path is imported through the prelude. Applies
+    /// with or without `--string-formats`, and replaces its type for the format. Can be given multiple times.
+    #[cfg_attr(feature = "cli", arg(
+        long = "format-type",
+        value_name = "FORMAT=PATH",
+        value_parser = FormatType::from_str,
+        action = clap::ArgAction::Append,
+    ))]
+    #[builder(via_mutators(init = Default::default()))]
+    pub format_overrides: Vec<FormatType>,
+
+    /// Types used for strings with well-known formats with `--string-formats`
+    #[cfg_attr(feature = "cli", arg(skip))]
+    pub format_types: FormatTypes,
+
+    /// Type used for properties marked with `x-kubernetes-embedded-resource`
+    ///
+    /// Embedded resources hold a whole Kubernetes object. `DynamicObject` does not implement `JsonSchema`, so
+    /// derived schemas describe it as any `serde_json::Value`.
+    #[builder(setter(into))]
+    #[cfg_attr(feature = "cli", arg(long, value_enum, default_value_t))]
+    pub embedded_resources: EmbeddedResources,
+
+    /// Types used for strings with a `date-time` or `date` format
+    ///
+    /// `time` needs its `serde-well-known` and `serde-human-readable` features, and only types fields and options of
+    /// date-times, keeping them as strings in lists and maps. `k8s` uses the k8s_openapi `Time` of conditions and
+    /// object metadata for date-times, and keeps dates as strings.
+    #[builder(setter(into))]
+    #[cfg_attr(feature = "cli", arg(long, value_enum, default_value_t))]
+    pub datetime: DateTimeBackend,
+
     /// Type used to represent maps via `additionalProperties`
     #[builder(setter(into))]
     #[cfg_attr(feature = "cli", arg(long, value_enum, default_value_t))]
     pub map_type: MapType,
 
+    /// Honor the `x-kubernetes-list-type` of arrays
+    ///
+    /// Lists of type `set` holding strings, integers or booleans become `BTreeSet`s. Structs with lists of type `map`
+    /// get methods finding (`get_ports_by_name`), inserting (`upsert_ports`) and removing (`remove_ports_by_name`)
+    /// items by their `x-kubernetes-list-map-keys`, when the items are generated structs with scalar keys.
+    #[cfg_attr(feature = "cli", arg(long))]
+    pub list_types: bool,
+
     /// Automatically removes `#[derive(Default)]` from structs that contain fields for
     /// which a default cannot be automatically derived.
     ///
@@ -208,30 +420,146 @@ impl TypeGenerator {
         &self,
         crd: &CustomResourceDefinition,
         args: Option<impl std::fmt::Display>,
+    ) -> anyhow::Result<String> {
+        let mut generated = String::new();
+
+        generated.push_str(&self.generation_header(args));
+
+        generated.push_str(&self.generate_crd(crd)?);
+
+        Ok(finalize(generated))
+    }
+
+    /// Generate rust types for several CRDs into a single output
+    ///
+    /// Every CRD is placed in its own module named after its lowercased kind
+    /// (suffixed by its group when several CRDs share a kind).
+    pub fn generate_rust_types_for_all(
+        &self,
+        crds: &[CustomResourceDefinition],
+        args: Option<impl std::fmt::Display>,
     ) -> anyhow::Result<String> {
         use std::fmt::Write;
 
-        let version = find_crd_version(crd, self.api_version.as_deref())?;
+        let mut generated = String::new();
 
-        let data = version
-            .schema
-            .as_ref()
-            .and_then(|schema| schema.open_api_v3_schema.clone());
+        generated.push_str(&self.generation_header(args));
 
-        let version_name = version.name.clone();
+        for (crd, module) in crds.iter().zip(crd_module_names(crds)) {
+            writeln!(&mut generated, "/// Types for `{}`", crd.name_any())?;
+            write_module(&mut generated, &module, &self.generate_crd(crd)?)?;
+        }
 
-        let (kind, plural, group, scope) = (
-            &crd.spec.names.kind,
-            &crd.spec.names.plural,
-            &crd.spec.group,
-            &crd.spec.scope,
-        );
+        Ok(finalize(generated))
+    }
 
-        let selectable_fields = version.selectable_fields.as_ref();
+    /// Generate rust types for several CRDs into a tree of module files
+    ///
+    /// Every CRD gets its own file, laid out according to `layout`, and every directory gets a
+    /// `mod.rs` declaring its modules. All paths are relative to the eventual output directory.
+    pub fn generate_files_for(
+        &self,
+        crds: &[CustomResourceDefinition],
+        layout: Layout,
+        args: Option<impl std::fmt::Display>,
+    ) -> anyhow::Result<Vec<GeneratedFile>> {
+        let mut tree = files::ModuleTree::default();
+
+        match layout {
+            Layout::Flat => {
+                for (crd, module) in crds.iter().zip(crd_module_names(crds)) {
+                    let doc = format!("/// Types for `{}`\n", crd.name_any());
+                    tree.insert(&[], &module, doc, finalize(self.generate_crd(crd)?))?;
+                }
+            }
+            Layout::GroupVersion => {
+                for crd in crds {
+                    let versions = if self.all_versions {
+                        served_crd_versions(crd)
+                    } else {
+                        vec![find_crd_version(crd, self.api_version.as_deref())?]
+                    };
+
+                    for version in versions {
+                        let parents = [module_name(&crd.spec.group), module_name(&version.name)];
+                        let attrs = version_attrs(crd, version);
+                        let body = finalize(self.generate_version(crd, version)?);
+                        let module = module_name(&crd.spec.names.kind.to_lowercase());
+                        tree.insert(&parents, &module, attrs, body)?;
+                    }
+                }
+            }
+        }
 
+        Ok(tree.into_files(&self.generation_header(args)))
+    }
+
+    /// Generate rust types for several CRDs into a tree of module files in `dir`
+    ///
+    /// See [`TypeGenerator::generate_files_for`]. Files that already have the generated contents are
+    /// left untouched so that their modification times do not trigger rebuilds. All changed files are
+    /// staged before any of them is replaced, and each is replaced atomically.
+    /// Returns the paths of the files that were written.
+    ///
+    /// Files of modules that are no longer generated are left in place. They are no longer declared in
+    /// any `mod.rs`, so they are not compiled, and can be removed.
+    pub fn write_files_for(
+        &self,
+        crds: &[CustomResourceDefinition],
+        dir: impl AsRef<std::path::Path>,
+        layout: Layout,
+        args: Option<impl std::fmt::Display>,
+    ) -> anyhow::Result<Vec<std::path::PathBuf>> {
+        let files = self.generate_files_for(crds, layout, args)?;
+        files::write_all_if_changed(
+            files
+                .iter()
+                .map(|file| (dir.as_ref().join(&file.path), file.contents.as_str())),
+        )
+    }
+
+    /// Generate the selected version(s) of a CRD without the generation warning
+    fn generate_crd(&self, crd: &CustomResourceDefinition) -> anyhow::Result<String> {
         let mut generated = String::new();
 
-        self.write_generation_warning(&mut generated, args)?;
+        if self.all_versions {
+            for version in served_crd_versions(crd) {
+                generated.push_str(&version_attrs(crd, version));
+                write_module(
+                    &mut generated,
+                    &module_name(&version.name),
+                    &self.generate_version(crd, version)?,
+                )?;
+            }
+        } else {
+            let version = find_crd_version(crd, self.api_version.as_deref())?;
+            generated.push_str(&self.generate_version(crd, version)?);
+        }
+
+        Ok(generated)
+    }
+
+    /// Generate the prelude and types for a single version of a CRD
+    fn generate_version(
+        &self,
+        crd: &CustomResourceDefinition,
+        version: &CustomResourceDefinitionVersion,
+    ) -> anyhow::Result<String> {
+        self.render(&self.generate_types(crd, version)?)
+    }
+
+    /// Analyze a single version of a CRD into the types to generate, without rendering them
+    ///
+    /// The result can be inspected, and rendered with [`TypeGenerator::render`].
+    pub fn generate_types(
+        &self,
+        crd: &CustomResourceDefinition,
+        version: &CustomResourceDefinitionVersion,
+    ) -> anyhow::Result<GeneratedTypes> {
+        let data = version
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.open_api_v3_schema.clone());
 
         let Some(schema) = data else {
             anyhow::bail!("no schema found for crd");
@@ -239,236 +567,212 @@ impl TypeGenerator {
 
         log::debug!("schema: {}", serde_json::to_string_pretty(&schema)?);
 
+        let known_types = self.enabled_known_types();
+        let format_types = self.enabled_format_types();
         let cfg = Config {
-            no_condition: self.no_condition,
-            no_object_reference: self.no_object_reference,
+            known_types: known_types.clone(),
+            format_types: format_types.clone(),
+            type_overrides: self.type_overrides.clone(),
             map: self.map_type,
+            embedded_resources: self.embedded_resources,
+            datetime: self.datetime,
+            explicit_null: self.explicit_null,
             relaxed: self.relaxed,
+            ..Config::default()
         };
 
-        let structs = analyze(schema, kind, cfg)?
+        let output = analyze(schema.clone(), &crd.spec.names.kind, cfg)?
             .rename()
+            .datetime(self.datetime)
+            .list_types(self.list_types)
             .builder_fields(self.builders)
-            .output();
-
-        if !self.hide_prelude {
-            self.write_prelude(&structs, &mut generated)?;
-        }
-
-        for container in &structs {
-            if container.level == 0 {
-                continue; // ignoring root struct
-            }
-
-            if self.elide.contains(&container.name) {
+            .schema_defaults(self.emit_defaults)
+            .constraints(self.validate)
+            .naming(self.naming)
+            .dedup(self.dedup_types)
+            .renames(&self.renames)?;
+        let warnings = output.warnings().to_vec();
+        let containers = output.output();
+
+        let elided = containers
+            .iter()
+            .filter(|container| self.elide.contains(&container.name))
+            .map(|container| {
                 log::debug!("eliding {} from the output", container.name);
-                continue;
-            }
-
-            self.write_docstr(&container.docs, "", &mut generated)?;
-
-            if container.is_main_container() {
-                self.write_derives(container, &structs, &mut generated)?;
-
-                //root struct gets kube derives unless opted out
-                if !self.hide_kube {
-                    let kind_upper_camel_case = kind.to_upper_camel_case();
-                    if kind != &kind_upper_camel_case {
-                        writeln!(
-                            &mut generated,
-                            r#"#[kube(group = "{}", version = "{}", kind = "{}", root = "{}", plural = "{}")]"#,
-                            group,
-                            version_name,
-                            kind,
-                            kind.to_upper_camel_case(),
-                            plural
-                        )?;
-                    } else {
-                        writeln!(
-                            &mut generated,
-                            r#"#[kube(group = "{}", version = "{}", kind = "{}", plural = "{}")]"#,
-                            group, version_name, kind, plural
-                        )?;
-                    }
-
-                    if scope == "Namespaced" {
-                        writeln!(&mut generated, r#"#[kube(namespaced)]"#)?;
-                    }
-                    if self.preserve_metadata {
-                        for (k, v) in crd.annotations() {
-                            writeln!(&mut generated, r#"#[kube(annotation("{}", "{}"))]"#, k, v)?;
-                        }
-                        for (k, v) in crd.labels() {
-                            writeln!(&mut generated, r#"#[kube(label("{}", "{}"))]"#, k, v)?;
-                        }
-                    }
-
-                    // status should be listed as a subresource
-                    // but also check for top-level .status for certain non-conforming crds like argo application
-                    if (version.subresources.as_ref().is_some_and(|c| c.status.is_some())
-                        || version
-                            .schema
-                            .as_ref()
-                            .and_then(|c| c.open_api_v3_schema.as_ref())
-                            .and_then(|c| c.properties.as_ref())
-                            .is_some_and(|c| c.contains_key("status")))
-                        && has_status_resource(&structs)
-                    {
-                        writeln!(
-                            &mut generated,
-                            r#"#[kube(status = "{}Status")]"#,
-                            kind.to_upper_camel_case(),
-                        )?;
-                    }
-
-                    if self.schema_mode != SchemaMode::Derived {
-                        writeln!(&mut generated, r#"#[kube(schema = "{}")]"#, self.schema_mode)?;
-                    }
-
-                    for derive in &self.derive_traits {
-                        if derive.derived_trait == "JsonSchema" {
-                            continue;
-                        }
+                container.name.clone()
+            })
+            .collect();
+
+        Ok(GeneratedTypes {
+            crd_name: crd.name_any(),
+            group: crd.spec.group.clone(),
+            version: version.name.clone(),
+            kind: crd.spec.names.kind.clone(),
+            imports: self.prelude_imports(&containers, &known_types, &format_types),
+            kube_attrs: self.kube_attrs(crd, version, &containers),
+            containers,
+            elided,
+            warnings,
+            original_schema: self
+                .original_schemas
+                .get(&crd.name_any(), &version.name)
+                .map(String::from),
+            schema,
+        })
+    }
 
-                        if !derive.is_applicable_to(container) {
-                            continue;
-                        }
+    /// Arguments for the `#[kube(...)]` attributes of the root struct
+    fn kube_attrs(
+        &self,
+        crd: &CustomResourceDefinition,
+        version: &CustomResourceDefinitionVersion,
+        structs: &[Container],
+    ) -> Vec<TokenStream> {
+        let mut attrs = vec![];
 
-                        if derive.derived_trait == "Default"
-                            && self.smart_derive_elision
-                            && !container.can_derive_default(&structs)
-                        {
-                            continue;
-                        }
+        if self.hide_kube {
+            return attrs;
+        }
 
-                        writeln!(&mut generated, r#"#[kube(derive="{}")]"#, derive.derived_trait)?;
-                    }
+        let (kind, plural, group, scope) = (
+            &crd.spec.names.kind,
+            &crd.spec.names.plural,
+            &crd.spec.group,
+            &crd.spec.scope,
+        );
 
-                    if let Some(selectable) = selectable_fields {
-                        write!(&mut generated, "{}", format_selectable(selectable))?;
-                    }
-                }
+        let kind_upper_camel_case = kind.to_upper_camel_case();
+        let version_name = &version.name;
+        if kind != &kind_upper_camel_case {
+            attrs.push(quote! {
+                group = #group, version = #version_name, kind = #kind, root = #kind_upper_camel_case, plural = #plural
+            });
+        } else {
+            attrs.push(quote!(group = #group, version = #version_name, kind = #kind, plural = #plural));
+        }
 
-                if container.is_enum {
-                    writeln!(&mut generated, "pub enum {} {{", container.name)?;
-                } else {
-                    writeln!(&mut generated, "pub struct {} {{", container.name)?;
-                }
-            } else {
-                self.write_derives(container, &structs, &mut generated)?;
+        if scope == "Namespaced" {
+            attrs.push(quote!(namespaced));
+        }
+        if self.preserve_metadata {
+            for (k, v) in crd.annotations() {
+                attrs.push(quote!(annotation(#k, #v)));
+            }
+            for (k, v) in crd.labels() {
+                attrs.push(quote!(label(#k, #v)));
+            }
+        }
 
-                let spec_trimmed_name = container.name.as_str().replace(
-                    &format!("{}Spec", kind.to_upper_camel_case()),
-                    &kind.to_upper_camel_case(),
-                );
+        // status should be listed as a subresource
+        // but also check for top-level .status for certain non-conforming crds like argo application
+        if (version.subresources.as_ref().is_some_and(|c| c.status.is_some())
+            || version
+                .schema
+                .as_ref()
+                .and_then(|c| c.open_api_v3_schema.as_ref())
+                .and_then(|c| c.properties.as_ref())
+                .is_some_and(|c| c.contains_key("status")))
+            && has_status_resource(structs)
+        {
+            let status = format!("{}Status", kind_upper_camel_case);
+            attrs.push(quote!(status = #status));
+        }
 
-                if container.is_enum {
-                    writeln!(&mut generated, "pub enum {} {{", spec_trimmed_name)?;
-                } else {
-                    writeln!(&mut generated, "pub struct {} {{", spec_trimmed_name)?;
-                }
+        match self.schema_mode {
+            SchemaMode::Derived => {}
+            // kube uses the JsonSchema impls embedding the preserved schema
+            SchemaMode::Preserved => attrs.push(quote!(schema = "manual")),
+            mode => {
+                let mode = mode.to_string();
+                attrs.push(quote!(schema = #mode));
             }
+        }
 
-            for member in &container.members {
-                self.write_docstr(&member.docs, "    ", &mut generated)?;
-
-                if !member.serde_annot.is_empty() {
-                    writeln!(&mut generated, "    #[serde({})]", member.serde_annot.join(", "))?;
+        if let Some(container) = structs.iter().find(|container| container.is_main_container()) {
+            for derive in &self.derive_traits {
+                if derive.derived_trait == "JsonSchema" {
+                    continue;
                 }
 
-                let name = quote::format_ident!("{}", member.name);
-
-                for annotation in &member.extra_annot {
-                    writeln!(&mut generated, "    {}", annotation)?;
+                if !derive.is_applicable_to(container) {
+                    continue;
                 }
 
-                let spec_trimmed_type = member.type_.as_str().replace(
-                    &format!("{}Spec", kind.to_upper_camel_case()),
-                    &kind.to_upper_camel_case(),
-                );
-
-                if container.is_enum {
-                    // NB: only supporting plain enumerations atm, not oneOf
-                    writeln!(&mut generated, "    {},", name)?;
-                } else {
-                    writeln!(&mut generated, "    pub {}: {},", name, spec_trimmed_type)?;
+                if derive.derived_trait == "Default"
+                    && self.smart_derive_elision
+                    && !container.can_derive_default(structs)
+                {
+                    continue;
                 }
-            }
 
-            writeln!(&mut generated, "}}")?;
-            writeln!(&mut generated)?;
+                let derived_trait = &derive.derived_trait;
+                attrs.push(quote!(derive = #derived_trait));
+            }
         }
 
-        let trim_to = generated.trim_end().len();
-
-        generated.truncate(trim_to);
-        generated.push('\n');
+        for field in version.selectable_fields.iter().flatten() {
+            let json_path = &field.json_path;
+            attrs.push(quote!(selectable = #json_path));
+        }
 
-        Ok(generated)
+        attrs
     }
 
-    fn write_docstr(
-        &self,
-        doc: &Option<String>,
-        indent: &str,
-        buffer: &mut impl std::fmt::Write,
-    ) -> anyhow::Result<()> {
-        // print doc strings if requested in arguments
-        if self.emit_docs {
-            if let Some(docstring) = doc {
-                writeln!(buffer, "{}", format_docstr(indent, docstring))?;
+    /// The known types to use, with the common ones for `--well-known-types`, and without those disabled by flags
+    /// or named like type overrides
+    fn enabled_known_types(&self) -> KnownTypes {
+        let mut known_types = self.known_types.clone();
+        if self.well_known_types {
+            for known in KnownTypes::common().iter() {
+                if !known_types.iter().any(|k| k.name() == known.name()) {
+                    known_types.register(known.clone());
+                }
             }
         }
-
-        Ok(())
-    }
-
-    fn write_derives(
-        &self,
-        struct_def: &Container,
-        containers: &[Container],
-        buffer: &mut impl std::fmt::Write,
-    ) -> anyhow::Result<()> {
-        let mut derives = vec!["Serialize", "Deserialize", "Clone", "Debug"];
-
-        if struct_def.is_main_container() && !self.hide_kube {
-            // CustomResource first for root struct
-            derives.insert(0, "CustomResource");
+        if self.no_condition {
+            known_types.remove("Condition");
         }
-
-        // TypedBuilder does not work with enums
-        if self.builders && !struct_def.is_enum {
-            derives.push("TypedBuilder");
+        if self.no_object_reference {
+            known_types.remove("ObjectReference");
         }
-
-        for derive in &self.derive_traits {
-            if derive.derived_trait == "Default"
-                && ((self.smart_derive_elision && !struct_def.can_derive_default(containers))
-                    || struct_def.is_enum)
-            {
-                continue;
-            }
-
-            if derive.is_applicable_to(struct_def) && !derives.contains(&derive.derived_trait.as_str()) {
-                derives.push(&derive.derived_trait)
-            }
+        for name in &self.no_known_types {
+            known_types.remove(name);
         }
+        // overrides take precedence, so they do not clash with known types of the same name
+        for type_override in &self.type_overrides {
+            known_types.remove(type_override.type_name());
+        }
+        known_types
+    }
 
-        writeln!(buffer, "#[derive({})]", derives.join(", "))?;
-
-        Ok(())
+    /// The format types to use; the registered ones with `--string-formats`, and those given by `--format-type`
+    pub(crate) fn enabled_format_types(&self) -> FormatTypes {
+        let mut format_types = if self.string_formats {
+            self.format_types.clone()
+        } else {
+            FormatTypes::empty()
+        };
+        for format_type in &self.format_overrides {
+            format_types.register(format_type.clone());
+        }
+        format_types
     }
 
-    fn write_prelude(&self, results: &[Container], buffer: &mut impl std::fmt::Write) -> anyhow::Result<()> {
-        writeln!(buffer, "#[allow(unused_imports)]")?;
-        writeln!(buffer, "mod prelude {{")?;
+    /// Paths to re-export from the prelude for the given containers
+    fn prelude_imports(
+        &self,
+        results: &[Container],
+        known_types: &KnownTypes,
+        format_types: &FormatTypes,
+    ) -> Vec<String> {
+        let mut imports = vec![];
 
         if !self.hide_kube {
-            writeln!(buffer, "    pub use kube::CustomResource;")?;
+            imports.push("kube::CustomResource");
         }
 
         if self.builders {
-            writeln!(buffer, "    pub use typed_builder::TypedBuilder;")?;
+            imports.push("typed_builder::TypedBuilder");
         }
 
         if self
@@ -476,80 +780,203 @@ impl TypeGenerator {
             .iter()
             .any(|derive| derive.derived_trait == "JsonSchema")
         {
-            writeln!(buffer, "    pub use schemars::JsonSchema;")?;
+            imports.push("schemars::JsonSchema");
         }
 
-        writeln!(buffer, "    pub use serde::{{Serialize, Deserialize}};")?;
+        imports.push("serde::{Serialize, Deserialize}");
+
+        if results.iter().any(|container| container.uses_repr()) {
+            imports.push("serde_repr::{Serialize_repr, Deserialize_repr}");
+            if self
+                .derive_traits
+                .iter()
+                .any(|derive| derive.derived_trait == "JsonSchema")
+            {
+                imports.push("schemars::JsonSchema_repr");
+            }
+        }
 
         if results.iter().any(|container| container.uses_btreemaps()) {
-            writeln!(buffer, "    pub use std::collections::BTreeMap;")?;
+            imports.push("std::collections::BTreeMap");
+        }
+
+        if results.iter().any(|container| container.uses_btreesets()) {
+            imports.push("std::collections::BTreeSet");
         }
 
         if results.iter().any(|container| container.uses_hashmaps()) {
-            writeln!(buffer, "    pub use std::collections::HashMap;")?;
+            imports.push("std::collections::HashMap");
         }
 
-        if results.iter().any(|container| container.uses_datetime()) {
-            writeln!(buffer, "    pub use chrono::{{DateTime, Utc}};")?;
+        // schemas of the non-standard `date` type use chrono without a backend
+        let datetime = self.datetime.or_chrono();
+        if let Some((_, import)) = datetime.date_time() {
+            if results.iter().any(|container| container.uses_datetime(datetime)) {
+                imports.push(import);
+            }
         }
 
-        if results.iter().any(|container| container.uses_date()) {
-            writeln!(buffer, "    pub use chrono::naive::NaiveDate;")?;
+        if let Some((_, import)) = datetime.date() {
+            if results.iter().any(|container| container.uses_date(datetime)) {
+                imports.push(import);
+            }
         }
 
         if results.iter().any(|container| container.uses_int_or_string()) {
-            writeln!(
-                buffer,
-                "    pub use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;"
-            )?;
+            imports.push("k8s_openapi::apimachinery::pkg::util::intstr::IntOrString");
         }
 
-        if results.iter().any(|container| container.contains_conditions()) && !self.no_condition {
-            writeln!(
-                buffer,
-                "    pub use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;"
-            )?;
+        let uses_type = |name| results.iter().any(|c| !c.is_root() && c.uses_type(name));
+        if uses_type("DynamicObject") {
+            imports.push("kube::core::DynamicObject");
         }
 
-        if results.iter().any(|container| container.contains_object_ref()) && !self.no_object_reference {
-            writeln!(buffer, "    pub use k8s_openapi::api::core::v1::ObjectReference;")?;
+        if uses_type("ObjectMeta") {
+            imports.push("k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta");
         }
 
-        writeln!(buffer, "}}\n")?;
-        writeln!(buffer, "use self::prelude::*;\n")?;
+        // the root is never rendered, so the known types of its members are not used
+        for known in known_types.iter() {
+            if results
+                .iter()
+                .any(|container| !container.is_root() && container.uses_type(known.name()))
+            {
+                imports.push(&known.path);
+            }
+        }
 
-        Ok(())
+        for format_type in format_types.iter() {
+            if let Some(path) = format_type.import() {
+                if !imports.contains(&path) && uses_type(format_type.type_name()) {
+                    imports.push(path);
+                }
+            }
+        }
+
+        for type_override in &self.type_overrides {
+            if let Some(path) = type_override.import() {
+                if !imports.contains(&path)
+                    && results.iter().any(|container| {
+                        !container.is_root() && container.uses_type(type_override.type_name())
+                    })
+                {
+                    imports.push(path);
+                }
+            }
+        }
+
+        imports.into_iter().map(String::from).collect()
     }
 
-    fn write_generation_warning(
-        &self,
-        buffer: &mut impl std::fmt::Write,
-        args: Option<impl std::fmt::Display>,
-    ) -> anyhow::Result<()> {
+    /// The comment header at the top of generated files
+    ///
+    /// It warns against manual changes, and records the kopium version along with the command `args` if given.
+    pub fn generation_header(&self, args: Option<impl std::fmt::Display>) -> String {
         let generated_by = env!("CARGO_PKG_NAME");
 
-        writeln!(
-            buffer,
-            "// WARNING: generated by {generated_by} - manual changes will be overwritten"
-        )?;
+        let mut header =
+            format!("// WARNING: generated by {generated_by} - manual changes will be overwritten\n");
 
         if let Some(args) = args {
-            writeln!(buffer, "// {generated_by} command: {generated_by} {}", args)?;
+            header.push_str(&format!("// {generated_by} command: {generated_by} {}\n", args));
         }
 
-        #[cfg(feature = "cli")]
-        let crate_version = clap::crate_version!();
+        header.push_str(&format!("// {generated_by} version: {VERSION}\n\n"));
+
+        header
+    }
+}
 
-        #[cfg(not(feature = "cli"))]
-        let crate_version = env!("CARGO_PKG_VERSION");
+/// Write `body` into a public module, indented one level
+fn write_module(buffer: &mut impl std::fmt::Write, name: &str, body: &str) -> anyhow::Result<()> {
+    writeln!(buffer, "pub mod {} {{", name)?;
+    for line in body.trim_end().lines() {
+        if line.is_empty() {
+            writeln!(buffer)?;
+        } else {
+            writeln!(buffer, "    {}", line)?;
+        }
+    }
+    writeln!(buffer, "}}")?;
+    writeln!(buffer)?;
+
+    Ok(())
+}
 
-        writeln!(buffer, "// {generated_by} version: {crate_version}")?;
-        writeln!(buffer,)?;
+/// Turn a CRD group or version name into a module name
+///
+/// Names starting with a digit get a leading `_`, and keywords are escaped as raw identifiers, or get a trailing
+/// `_` for the keywords that cannot be raw identifiers (e.g. `r#type` and `self_`).
+fn module_name(name: &str) -> String {
+    let name = name.replace(['-', '.'], "_");
+    let name = if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    };
+    match name.as_str() {
+        "crate" | "self" | "super" | "Self" => format!("{name}_"),
+        _ if syn::parse_str::<syn::Ident>(&name).is_err() => format!("r#{name}"),
+        _ => name,
+    }
+}
+
+/// The doc comment and attributes of the module for a CRD version
+///
+/// The doc marks the storage version, and deprecated versions get a `#[deprecated]` attribute.
+fn version_attrs(crd: &CustomResourceDefinition, version: &CustomResourceDefinitionVersion) -> String {
+    let role = if version.storage { " (storage version)" } else { "" };
+    let mut attrs = format!(
+        "/// Types for the `{}` version of `{}`{}\n",
+        version.name,
+        crd.name_any(),
+        role
+    );
+    if version.deprecated == Some(true) {
+        attrs.push_str(&deprecated_attr(version));
+    }
+    attrs
+}
 
-        Ok(())
+/// The `#[deprecated]` attribute for a deprecated CRD version
+fn deprecated_attr(version: &CustomResourceDefinitionVersion) -> String {
+    match &version.deprecation_warning {
+        Some(warning) => format!("#[deprecated(note = {:?})]\n", warning),
+        None => "#[deprecated]\n".to_string(),
     }
 }
 
+/// Trim trailing whitespace from generated output, leaving a single newline
+fn finalize(mut generated: String) -> String {
+    let trim_to = generated.trim_end().len();
+
+    generated.truncate(trim_to);
+    generated.push('\n');
+
+    generated
+}
+
+/// Module names for a set of CRDs; the lowercased kind, or kind and group if the kind is ambiguous
+///
+/// Kinds that are keywords are escaped (e.g. `r#type` for `Type`).
+pub fn crd_module_names(crds: &[CustomResourceDefinition]) -> Vec<String> {
+    crds.iter()
+        .map(|crd| {
+            let kind = crd.spec.names.kind.to_lowercase();
+            if crds
+                .iter()
+                .filter(|other| other.spec.names.kind.to_lowercase() == kind)
+                .count()
+                > 1
+            {
+                format!("{}_{}", kind, module_name(&crd.spec.group))
+            } else {
+                module_name(&kind)
+            }
+        })
+        .collect()
+}
+
 pub fn find_crd_version<'a>(
     crd: &'a CustomResourceDefinition,
     version: Option<&str>,
@@ -574,6 +1001,20 @@ pub fn find_crd_version<'a>(
     }
 }
 
+/// All served versions of a CRD, ordered from the highest to the lowest priority
+pub fn served_crd_versions(crd: &CustomResourceDefinition) -> Vec<&CustomResourceDefinitionVersion> {
+    let mut versions = crd
+        .spec
+        .versions
+        .iter()
+        .filter(|crd_version| crd_version.served)
+        .collect::<Vec<_>>();
+
+    versions
+        .sort_by_cached_key(|crd_version| std::cmp::Reverse(Version::parse(&crd_version.name).priority()));
+    versions
+}
+
 pub fn all_crd_versions(crd: &CustomResourceDefinition) -> String {
     let mut versions = crd
         .spec
@@ -591,3 +1032,76 @@ pub fn has_status_resource(results: &[Container]) -> bool {
         .iter()
         .any(|container| container.is_status_container() && !container.members.is_empty())
 }
+
+#[cfg(test)]
+mod test {
+    use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+
+    use super::{crd_module_names, crds_from_str, module_name, Layout, TypeGenerator};
+
+    #[test]
+    fn module_names_are_identifiers() {
+        assert_eq!(module_name("monitoring.coreos.com"), "monitoring_coreos_com");
+        assert_eq!(module_name("v1beta1"), "v1beta1");
+        assert_eq!(module_name("1clux.dev"), "_1clux_dev");
+        assert_eq!(module_name("type"), "r#type");
+        assert_eq!(module_name("self"), "self_");
+        assert_eq!(module_name("Self"), "Self_");
+    }
+
+    #[test]
+    fn crd_module_names_are_identifiers() {
+        let crd = |kind: &str, group: &str| {
+            let crd_str = format!(
+                r#"
+                metadata:
+                  name: x
+                spec:
+                  group: {group}
+                  names:
+                    kind: {kind}
+                    plural: x
+                  scope: Namespaced
+                  versions: []
+"#
+            );
+            serde_yaml::from_str::<CustomResourceDefinition>(&crd_str).unwrap()
+        };
+        let crds = [
+            crd("Type", "example.com"),
+            crd("Foo", "a.com"),
+            crd("Foo", "b.com"),
+        ];
+        assert_eq!(crd_module_names(&crds), ["r#type", "foo_a_com", "foo_b_com"]);
+    }
+
+    #[test]
+    fn group_version_modules_describe_their_version() {
+        let crds = crds_from_str(include_str!(
+            "../tests/cmd/generate/crds/multiversions.clux.dev.yaml"
+        ))
+        .unwrap();
+        let generator = TypeGenerator::builder().all_versions(true).build();
+        let files = generator
+            .generate_files_for(&crds, Layout::GroupVersion, None::<String>)
+            .unwrap();
+        let mod_rs = |path: &str| {
+            let file = files
+                .iter()
+                .find(|file| file.path.to_str() == Some(path))
+                .unwrap();
+            file.contents.split_once("\n\n").unwrap().1.to_string()
+        };
+
+        // the same docs and attributes as the modules of versions generated into one file
+        assert_eq!(
+            mod_rs("clux_dev/v1/mod.rs"),
+            "/// Types for the `v1` version of `multiversions.clux.dev` (storage version)\npub mod multiversion;\n"
+        );
+        assert_eq!(
+            mod_rs("clux_dev/v1beta1/mod.rs"),
+            "/// Types for the `v1beta1` version of `multiversions.clux.dev`\n#[deprecated(note = \"clux.dev/v1beta1 \
+             MultiVersion is deprecated; use clux.dev/v1 MultiVersion\")]\npub mod multiversion;\n"
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Renders methods finding, inserting and removing the items of lists of type `map` by their
+//! `x-kubernetes-list-map-keys`
+use std::collections::HashMap;
+
+use heck::ToSnakeCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::{
+    render::{parse_item, parse_type, unwrap_generic},
+    Container, GeneratedTypes, ListType, Member,
+};
+
+/// An impl with the accessors of all map lists of a struct, if it has any
+///
+/// `Output::list_types` only keeps map lists of generated structs whose keys are scalar members.
+pub(crate) fn list_map_items(
+    types: &GeneratedTypes,
+    container: &Container,
+) -> anyhow::Result<Vec<syn::Item>> {
+    if container.is_enum {
+        return Ok(vec![]);
+    }
+    let renames = types.renames();
+
+    let mut methods = vec![];
+    for member in &container.members {
+        let Some(ListType::Map(keys)) = &member.list_type else {
+            continue;
+        };
+        let optional = unwrap_generic(&member.type_, "Option<");
+        let Some(item) = unwrap_generic(optional.unwrap_or(&member.type_), "Vec<") else {
+            continue;
+        };
+        let Some(item_container) = types.containers.iter().find(|c| c.name == item) else {
+            continue;
+        };
+        let Some(keys) = keys
+            .iter()
+            .map(|key| item_container.members.iter().find(|m| m.original_name() == key))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        let field = format_ident!("{}", member.name);
+        let (items, items_mut, items_insert) = if optional.is_some() {
+            (
+                quote!(self.#field.as_ref()?),
+                quote!(self.#field.as_mut()?),
+                quote!(self.#field.get_or_insert_with(Vec::new)),
+            )
+        } else {
+            (
+                quote!(&self.#field),
+                quote!(&mut self.#field),
+                quote!(&mut self.#field),
+            )
+        };
+        let item_type = parse_type(item, &renames)?;
+
+        let params = keys
+            .iter()
+            .map(|key| {
+                let name = format_ident!("{}", key.name);
+                let ty = key_type(key, &renames)?;
+                Ok(quote!(#name: #ty))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let matches_keys = keys.iter().map(|key| {
+            let name = format_ident!("{}", key.name);
+            match key.type_.as_str() {
+                "Option<String>" => quote!(item.#name.as_deref() == #name),
+                _ => quote!(item.#name == #name),
+            }
+        });
+        let matches_keys = quote!(#(#matches_keys)&&*);
+        let matches_item = keys.iter().map(|key| {
+            let name = format_ident!("{}", key.name);
+            quote!(existing.#name == item.#name)
+        });
+
+        let list = member.original_name();
+        let key_names = keys
+            .iter()
+            .map(|key| format!("`{}`", key.original_name()))
+            .collect::<Vec<_>>();
+        let key_names = match key_names.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+            None => continue,
+        };
+        let get_doc = format!(" The item of `{list}` with the given {key_names}");
+        let get_mut_doc = format!(" The item of `{list}` with the given {key_names}, for modifying it");
+        let upsert_doc = format!(
+            " Insert an item into `{list}`, replacing and returning any item with the same {key_names}"
+        );
+        let remove_doc = format!(" Remove the item of `{list}` with the given {key_names}");
+
+        let field_name = member.name.trim_start_matches("r#");
+        let by_keys = keys
+            .iter()
+            .map(|key| key.name.trim_start_matches("r#").to_snake_case())
+            .collect::<Vec<_>>()
+            .join("_and_");
+        let get = format_ident!("get_{}_by_{}", field_name, by_keys);
+        let get_mut = format_ident!("get_{}_by_{}_mut", field_name, by_keys);
+        let upsert = format_ident!("upsert_{}", field_name);
+        let remove = format_ident!("remove_{}_by_{}", field_name, by_keys);
+
+        methods.push(quote! {
+            #[doc = #get_doc]
+            pub fn #get(&self, #(#params),*) -> Option<&#item_type> {
+                let items = #items;
+                items.iter().find(|item| #matches_keys)
+            }
+
+            #[doc = #get_mut_doc]
+            pub fn #get_mut(&mut self, #(#params),*) -> Option<&mut #item_type> {
+                let items = #items_mut;
+                items.iter_mut().find(|item| #matches_keys)
+            }
+
+            #[doc = #upsert_doc]
+            pub fn #upsert(&mut self, item: #item_type) -> Option<#item_type> {
+                let items = #items_insert;
+                match items.iter().position(|existing| #(#matches_item)&&*) {
+                    Some(index) => Some(std::mem::replace(&mut items[index], item)),
+                    None => {
+                        items.push(item);
+                        None
+                    }
+                }
+            }
+
+            #[doc = #remove_doc]
+            pub fn #remove(&mut self, #(#params),*) -> Option<#item_type> {
+                let items = #items_mut;
+                let index = items.iter().position(|item| #matches_keys)?;
+                Some(items.remove(index))
+            }
+        });
+    }
+
+    if methods.is_empty() {
+        return Ok(vec![]);
+    }
+    let name = format_ident!("{}", types.type_name(container));
+    Ok(vec![parse_item(quote! {
+        impl #name {
+            #(#methods)*
+        }
+    })?])
+}
+
+/// The parameter type of a key, borrowing strings
+fn key_type(key: &Member, renames: &HashMap<String, String>) -> anyhow::Result<TokenStream> {
+    Ok(match key.type_.as_str() {
+        "String" => quote!(&str),
+        "Option<String>" => quote!(Option<&str>),
+        type_ => {
+            let ty = parse_type(type_, renames)?;
+            quote!(#ty)
+        }
+    })
+}
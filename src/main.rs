@@ -27,9 +27,26 @@ mod cli {
         crd: Option<String>,
 
         /// Point to the location of a CRD to use on disk
+        ///
+        /// This can be a file or a directory of files, and files can contain multiple
+        /// YAML documents or a `List` of CRDs. Use `-` to read from stdin.
         #[arg(long = "filename", short, conflicts_with("crd"))]
         file: Option<PathBuf>,
 
+        /// Write the generated types into this directory instead of printing them
+        ///
+        /// Every CRD is written into its own file, and every directory gets a `mod.rs` declaring its modules.
+        /// Files are only rewritten when their contents change.
+        #[arg(long, short = 'o')]
+        output_dir: Option<PathBuf>,
+
+        /// Directory layout of the files written to --output-dir
+        ///
+        /// `flat` writes one file per CRD named after its kind, `group-version` writes one file per CRD version
+        /// into `<group>/<version>/<kind>.rs` directories.
+        #[arg(long, value_enum, default_value_t, requires = "output_dir")]
+        layout: kopium::Layout,
+
         #[command(subcommand)]
         command: Option<Command>,
 
@@ -95,12 +112,12 @@ mod cli {
     }
 
     impl Kopium {
-        async fn dispatch(&self) -> anyhow::Result<()> {
-            if let Some(name) = self.crd.as_deref() {
-                return self.generate_types_for_fetched_crd(name).await;
+        async fn dispatch(&mut self) -> anyhow::Result<()> {
+            if let Some(name) = self.crd.clone() {
+                return self.generate_types_for_fetched_crd(&name).await;
             }
 
-            if let Some(file) = self.file.as_deref() {
+            if let Some(file) = self.file.clone() {
                 return self.generate_types_for_file(file).await;
             }
 
@@ -142,40 +159,71 @@ mod cli {
             Ok(())
         }
 
-        async fn generate_types_for(&self, crd: &CustomResourceDefinition) -> anyhow::Result<()> {
+        async fn generate_types_for(&self, crds: &[CustomResourceDefinition]) -> anyhow::Result<()> {
             let args = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
 
-            let generated = self.generator.generate_rust_types_for(crd, Some(args))?;
+            if let Some(dir) = self.output_dir.as_deref() {
+                for path in self
+                    .generator
+                    .write_files_for(crds, dir, self.layout, Some(args))?
+                {
+                    log::info!("wrote {}", path.display());
+                }
+
+                return Ok(());
+            }
+
+            let generated = match crds {
+                [crd] => self.generator.generate_rust_types_for(crd, Some(args))?,
+                crds => self.generator.generate_rust_types_for_all(crds, Some(args))?,
+            };
 
             println!("{generated}");
 
             Ok(())
         }
 
-        async fn generate_types_for_file(&self, target: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        async fn generate_types_for_file(
+            &mut self,
+            target: impl AsRef<std::path::Path>,
+        ) -> anyhow::Result<()> {
             let target = target.as_ref();
 
             // no cluster access needed in this case
-            let data = if target == <str as AsRef<std::path::Path>>::as_ref("-") {
-                get_stdin_data().with_context(|| "Failed to read from stdin".to_string())?
+            let preserved = self.generator.schema_mode == kopium::SchemaMode::Preserved;
+            let crds = if target == <str as AsRef<std::path::Path>>::as_ref("-") {
+                let data = get_stdin_data().with_context(|| "Failed to read from stdin".to_string())?;
+                if preserved {
+                    self.generator.original_schemas = kopium::schemas_from_str(&data)?;
+                }
+                kopium::crds_from_str(&data)?
             } else {
-                std::fs::read_to_string(target)
-                    .with_context(|| format!("Failed to read {}", target.display()))?
+                if preserved {
+                    self.generator.original_schemas = kopium::schemas_from_path(target)?;
+                }
+                kopium::crds_from_path(target)?
             };
 
-            let crd = serde_yaml::from_str::<CustomResourceDefinition>(&data)?;
+            if crds.is_empty() {
+                anyhow::bail!("No CustomResourceDefinitions found in {}", target.display());
+            }
 
-            self.generate_types_for(&crd).await
+            self.generate_types_for(&crds).await
         }
 
-        async fn generate_types_for_fetched_crd(&self, target: &str) -> anyhow::Result<()> {
+        async fn generate_types_for_fetched_crd(&mut self, target: &str) -> anyhow::Result<()> {
+            // fetched untyped, to embed the schema as served with --schema=preserved
+            let resource = kube::api::ApiResource::erase::<CustomResourceDefinition>(&());
             let api = kube::Client::try_default()
                 .await
-                .map(kube::Api::<CustomResourceDefinition>::all)?;
+                .map(|client| kube::Api::<kube::api::DynamicObject>::all_with(client, &resource))?;
 
-            let crd = api.get(target).await?;
+            let data = serde_json::to_string(&api.get(target).await?)?;
+            if self.generator.schema_mode == kopium::SchemaMode::Preserved {
+                self.generator.original_schemas = kopium::schemas_from_str(&data)?;
+            }
 
-            self.generate_types_for(&crd).await
+            self.generate_types_for(&kopium::crds_from_str(&data)?).await
         }
     }
 }
@@ -0,0 +1,400 @@
+//! Naming strategies for generated types
+use std::collections::{HashMap, HashSet};
+
+use heck::ToUpperCamelCase;
+
+use crate::{output::rust_name, Container};
+
+/// How generated types are named
+///
+/// The custom resource, its spec and its status are always named after the kind.
+#[derive(
+    // std
+    Eq,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    // strum
+    strum::Display,
+    strum::AsRefStr,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum NamingStrategy {
+    /// The kind followed by every property on the path to the type, e.g. `FooEndpointsTlsConfigCa`
+    #[default]
+    Full,
+    /// The kind followed by the shortest end of the path that no other type ends with, e.g. `FooTlsConfigCa`
+    Suffix,
+    /// The kind followed by the property, or the parent type followed by the property when that name is shared,
+    /// e.g. `FooCa`
+    Leaf,
+    /// The kind followed by the schema `title`, or the type name starting the description of an object (as in
+    /// `SafeTLSConfig specifies ...`), or else named as by `leaf`
+    Title,
+}
+
+/// New names of the containers under a naming strategy, by their analyzed names
+///
+/// Names that are already taken (also once the `Spec` is dropped from types below the spec) are resolved by falling
+/// back to the analyzed name, and then to numbered names, so renaming never merges two types.
+pub(crate) fn type_names(containers: &[Container], strategy: NamingStrategy) -> HashMap<String, String> {
+    let Some(root) = containers.iter().find(|c| c.level == 0) else {
+        return HashMap::new();
+    };
+    if strategy == NamingStrategy::Full {
+        return HashMap::new();
+    }
+    let kind = root.name.as_str();
+    let tree = Tree::new(containers, kind);
+
+    let mut names = Names {
+        kind,
+        taken: HashSet::new(),
+        renames: HashMap::new(),
+    };
+    for c in containers.iter().filter(|c| c.is_named_after_kind()) {
+        names.taken.insert(rust_name(kind, &c.name));
+        names.renames.insert(c.name.clone(), c.name.clone());
+    }
+    // types that are used but not generated (e.g. known types) keep their names
+    for m in containers.iter().flat_map(|c| &c.members) {
+        for part in m.type_.split(|c: char| !c.is_alphanumeric() && c != '_') {
+            if !part.is_empty() && !containers.iter().any(|c| c.name == part) {
+                names.taken.insert(part.to_string());
+            }
+        }
+    }
+
+    // shallower types are named first, and keep the plainer names
+    let mut renamed = containers
+        .iter()
+        .filter(|c| !c.is_named_after_kind())
+        .collect::<Vec<_>>();
+    renamed.sort_by_key(|c| c.level);
+    let renamed = renamed.into_iter();
+    match strategy {
+        NamingStrategy::Full => {}
+        NamingStrategy::Suffix => {
+            let paths = renamed.clone().map(|c| tree.path(&c.name)).collect::<Vec<_>>();
+            for (c, path) in renamed.zip(&paths) {
+                let candidates = (1..=path.len())
+                    .filter(|&n| {
+                        !paths
+                            .iter()
+                            .any(|other| other != path && other.ends_with(&path[path.len() - n..]))
+                    })
+                    .map(|n| format!("{}{}", kind, path[path.len() - n..].concat()));
+                names.assign(&c.name, candidates);
+            }
+        }
+        NamingStrategy::Leaf | NamingStrategy::Title => {
+            let hints = renamed
+                .clone()
+                .map(|c| {
+                    let hint = match strategy {
+                        NamingStrategy::Title => name_hint(c).unwrap_or_else(|| tree.key(&c.name)),
+                        _ => tree.key(&c.name),
+                    };
+                    match hint.strip_prefix(kind) {
+                        Some(rest) if rest.starts_with(|c: char| c.is_uppercase()) => hint,
+                        _ => format!("{kind}{hint}"),
+                    }
+                })
+                .collect::<Vec<_>>();
+            for (c, hint) in renamed.zip(&hints) {
+                let shared = hints.iter().filter(|other| *other == hint).count() > 1;
+                let parent = tree.parent(&c.name).map(|parent| {
+                    let parent = names.name(parent);
+                    format!("{}{}", parent, hint.strip_prefix(kind).unwrap_or(hint))
+                });
+                let candidates = (!shared).then(|| hint.clone()).into_iter().chain(parent);
+                names.assign(&c.name, candidates);
+            }
+        }
+    }
+    names.renames.retain(|name, rename| name != rename);
+    names.renames
+}
+
+/// A type name given as the schema `title`, or starting the description of an object (e.g. `SafeTLSConfig ...`)
+fn name_hint(container: &Container) -> Option<String> {
+    if let Some(title) = container.title.as_deref().filter(|t| !t.trim().is_empty()) {
+        return Some(title.to_upper_camel_case());
+    }
+    if container.is_enum {
+        return None; // enums of strings have the description of the object they are in
+    }
+    let word = container.docs.as_deref()?.split_whitespace().next()?;
+    is_type_name(word).then(|| word.to_upper_camel_case())
+}
+
+/// Whether a word reads as a type name rather than a capitalised word, i.e. is mixed case with an inner capital
+pub(crate) fn is_type_name(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_alphanumeric())
+        && word.starts_with(|c: char| c.is_ascii_uppercase())
+        && word.contains(|c: char| c.is_ascii_lowercase())
+        && word.chars().skip(1).any(|c| c.is_ascii_uppercase())
+}
+
+/// The containers referring to each container, and the properties they refer to it by
+struct Tree<'a> {
+    kind: &'a str,
+    parents: HashMap<&'a str, (&'a str, String)>,
+}
+
+impl<'a> Tree<'a> {
+    fn new(containers: &'a [Container], kind: &'a str) -> Self {
+        let names = containers.iter().map(|c| c.name.as_str()).collect::<HashSet<_>>();
+        let mut parents = HashMap::new();
+        for c in containers {
+            for m in &c.members {
+                let types = m.type_.split(|ch: char| !ch.is_alphanumeric() && ch != '_');
+                for type_ in types.filter_map(|t| names.get(t)) {
+                    if *type_ != c.name && !parents.contains_key(type_) {
+                        parents.insert(*type_, (c.name.as_str(), m.original_name().to_upper_camel_case()));
+                    }
+                }
+            }
+        }
+        Tree { kind, parents }
+    }
+
+    fn parent(&self, name: &str) -> Option<&'a str> {
+        self.parents.get(name).map(|(parent, _)| *parent)
+    }
+
+    /// The property of the parent holding the container, in camel case
+    fn key(&self, name: &str) -> String {
+        match self.parents.get(name) {
+            Some((_, key)) => key.clone(),
+            None => name.strip_prefix(self.kind).unwrap_or(name).to_string(),
+        }
+    }
+
+    /// The properties from the root to the container, in camel case
+    fn path(&self, name: &str) -> Vec<String> {
+        let mut path = vec![];
+        let mut current = name;
+        while let Some((parent, key)) = self.parents.get(current) {
+            if path.len() > self.parents.len() {
+                break; // types referring to themselves through their children
+            }
+            path.push(key.clone());
+            current = parent;
+        }
+        if current != self.kind {
+            path.push(self.key(current));
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Names assigned so far
+struct Names<'a> {
+    kind: &'a str,
+    taken: HashSet<String>,
+    renames: HashMap<String, String>,
+}
+
+impl Names<'_> {
+    /// The new name of a container, or its analyzed name if it is not named yet
+    fn name(&self, name: &str) -> String {
+        self.renames
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Name a container after the first of the candidates that is not taken
+    fn assign(&mut self, name: &str, candidates: impl Iterator<Item = String>) {
+        let mut candidates = candidates.chain(std::iter::once(name.to_string()));
+        let rename = candidates
+            .find(|candidate| !self.taken.contains(&rust_name(self.kind, candidate)))
+            .unwrap_or_else(|| {
+                (2..)
+                    .map(|n| format!("{name}{n}"))
+                    .find(|candidate| !self.taken.contains(&rust_name(self.kind, candidate)))
+                    .unwrap()
+            });
+        self.taken.insert(rust_name(self.kind, &rename));
+        self.renames.insert(name.to_string(), rename);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::JSONSchemaProps;
+
+    use super::NamingStrategy;
+    use crate::{
+        analyzer::{analyze, Config},
+        KnownTypes,
+    };
+
+    fn type_names(strategy: NamingStrategy) -> Vec<String> {
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              endpoints:
+                items:
+                  properties:
+                    tlsConfig:
+                      properties:
+                        ca:
+                          properties:
+                            key:
+                              type: string
+                          type: object
+                      type: object
+                  type: object
+                type: array
+              protocol:
+                enum:
+                - tcp
+                - udp
+                title: ProtocolMode
+                type: string
+              proxy:
+                properties:
+                  tlsConfig:
+                    description: SafeTLSConfig specifies TLS settings.
+                    properties:
+                      ca:
+                        properties:
+                          name:
+                            type: string
+                        type: object
+                      insecure:
+                        type: boolean
+                    type: object
+                  mode:
+                    enum:
+                    - fast
+                    - safe
+                    title: ProxyMode
+                    type: string
+                type: object
+            type: object
+          status:
+            properties:
+              proxy:
+                properties:
+                  ready:
+                    type: boolean
+                type: object
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let output = analyze(schema, "Foo", Config::default()).unwrap();
+        let output = output.rename().naming(strategy);
+        output.output().into_iter().map(|c| c.name).collect()
+    }
+
+    #[test]
+    fn naming_strategies() {
+        assert_eq!(type_names(NamingStrategy::Full), [
+            "Foo",
+            "FooSpec",
+            "FooSpecEndpoints",
+            "FooSpecEndpointsTlsConfig",
+            "FooSpecEndpointsTlsConfigCa",
+            "FooSpecProtocol",
+            "FooSpecProxy",
+            "FooSpecProxyMode",
+            "FooSpecProxyTlsConfig",
+            "FooSpecProxyTlsConfigCa",
+            "FooStatus",
+            "FooStatusProxy",
+        ]);
+        // the spec and status keep their names, and the proxy of the status disambiguates the proxy of the spec
+        assert_eq!(type_names(NamingStrategy::Suffix), [
+            "Foo",
+            "FooSpec",
+            "FooEndpoints",
+            "FooEndpointsTlsConfig",
+            "FooEndpointsTlsConfigCa",
+            "FooProtocol",
+            "FooSpecProxy",
+            "FooMode",
+            "FooProxyTlsConfig",
+            "FooProxyTlsConfigCa",
+            "FooStatus",
+            "FooStatusProxy",
+        ]);
+        // shared names are prefixed by their (renamed) parents
+        assert_eq!(type_names(NamingStrategy::Leaf), [
+            "Foo",
+            "FooSpec",
+            "FooEndpoints",
+            "FooEndpointsTlsConfig",
+            "FooEndpointsTlsConfigCa",
+            "FooProtocol",
+            "FooSpecProxy",
+            "FooMode",
+            "FooSpecProxyTlsConfig",
+            "FooSpecProxyTlsConfigCa",
+            "FooStatus",
+            "FooStatusProxy",
+        ]);
+        // titles also name the enums directly under the spec, as only the spec and status keep their names
+        assert_eq!(type_names(NamingStrategy::Title), [
+            "Foo",
+            "FooSpec",
+            "FooEndpoints",
+            "FooTlsConfig",
+            "FooTlsConfigCa",
+            "FooProtocolMode",
+            "FooSpecProxy",
+            "FooProxyMode",
+            "FooSafeTlsConfig",
+            "FooSafeTlsConfigCa",
+            "FooStatus",
+            "FooStatusProxy",
+        ]);
+    }
+
+    #[test]
+    fn known_type_names_are_taken() {
+        let schema_str = r#"
+        properties:
+          spec:
+            properties:
+              deep:
+                properties:
+                  var:
+                    properties:
+                      path:
+                        type: string
+                    type: object
+                type: object
+              extra:
+                properties:
+                  name:
+                    type: string
+                  valueFrom:
+                    properties:
+                      path:
+                        type: string
+                    type: object
+                type: object
+            type: object
+        type: object
+        "#;
+        let schema: JSONSchemaProps = serde_yaml::from_str(schema_str).unwrap();
+        let cfg = Config {
+            known_types: KnownTypes::common(),
+            ..Config::default()
+        };
+        let output = analyze(schema, "Env", cfg).unwrap();
+        let containers = output.rename().naming(NamingStrategy::Leaf).output();
+        // the leaf name of spec.deep.var is that of the EnvVar of spec.extra
+        assert_eq!(containers[1].members[1].type_, "Option<EnvVar>");
+        assert_eq!(containers[3].name, "EnvDeepVar");
+    }
+}
@@ -1,11 +1,23 @@
-use std::{cell::OnceCell, fmt::Write, sync::OnceLock};
+use std::{
+    cell::OnceCell,
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    sync::OnceLock,
+};
 
+use anyhow::{anyhow, bail};
 use heck::{ToPascalCase, ToSnakeCase};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::ValidationRule;
 use regex::{Regex, RegexBuilder};
 
-/// All found containers
+use crate::{formats, naming, NamingStrategy, Rename};
+
+/// All found containers, along with any warnings raised while finding them
 #[derive(Default, Debug)]
-pub struct Output(Vec<Container>);
+pub struct Output {
+    containers: Vec<Container>,
+    warnings: Vec<String>,
+}
 
 impl Output {
     /// Safe container inserter
@@ -16,7 +28,7 @@ impl Output {
     /// 2. identical structs with different names are deduplicated https://github.com/kube-rs/kopium/issues/298
     pub fn insert(&mut self, mut value: Container) -> bool {
         let mut name_clashes = 0;
-        for c in &self.0 {
+        for c in &self.containers {
             if c == &value {
                 return false; // no new value inserted
             }
@@ -32,34 +44,83 @@ impl Output {
             name_clashes -= 1;
         }
         // push the struct/enum (possibly with a new unique name)
-        self.0.push(value);
+        self.containers.push(value);
         true // new value inserted
     }
 
     /// Consume self and return the final container vec
     pub fn output(self) -> Vec<Container> {
-        self.0
+        self.containers
+    }
+
+    /// The containers found so far
+    pub fn containers(&self) -> &[Container] {
+        &self.containers
     }
 
     /// Extend the inner vector with another Output instance
     pub fn extend(&mut self, extras: Output) {
-        self.0.extend(extras.0);
+        self.containers.extend(extras.containers);
+        self.warnings.extend(extras.warnings);
+    }
+
+    /// Log a warning and record it in the output
+    pub fn warn(&mut self, warning: String) {
+        log::warn!("{}", warning);
+        self.warnings.push(warning);
+    }
+
+    /// Warnings raised during analysis
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
     }
 }
 
 impl PartialEq for Container {
     fn eq(&self, other: &Self) -> bool {
-        (&self.name, &self.members, &self.is_enum) == (&other.name, &other.members, &other.is_enum)
+        (
+            &self.name,
+            &self.members,
+            &self.is_enum,
+            &self.serde_annot,
+            &self.default_variant,
+            &self.repr,
+            &self.rules,
+        ) == (
+            &other.name,
+            &other.members,
+            &other.is_enum,
+            &other.serde_annot,
+            &other.default_variant,
+            &other.repr,
+            &other.rules,
+        )
     }
 }
 impl PartialEq for Member {
     fn eq(&self, other: &Self) -> bool {
-        (&self.name, &self.type_, &self.serde_annot) == (&other.name, &other.type_, &other.serde_annot)
+        (
+            &self.name,
+            &self.type_,
+            &self.serde_annot,
+            &self.default,
+            &self.constraints,
+            &self.rules,
+            &self.list_type,
+        ) == (
+            &other.name,
+            &other.type_,
+            &other.serde_annot,
+            &other.default,
+            &other.constraints,
+            &other.rules,
+            &other.list_type,
+        )
     }
 }
 
 /// Output container found by analyzer
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Container {
     /// The short name of the struct (kind + capitalized suffix)
     pub name: String,
@@ -69,13 +130,31 @@ pub struct Container {
     pub members: Vec<Member>,
     /// Documentation properties extracted for the container
     pub docs: Option<String>,
+    /// The `title` of the container schema
+    ///
+    /// This is used to name the container with `NamingStrategy::Title`.
+    pub title: Option<String>,
     /// Whether this container is an enum
     pub is_enum: bool,
+    /// Serde annotations that should prefix the container
+    ///
+    /// This is used to select the representation of data-carrying enums, e.g. `untagged`.
+    pub serde_annot: Vec<String>,
+    /// The variant named by the schema `default` of an enum
+    ///
+    /// This follows the member name through `Container::rename`.
+    pub default_variant: Option<String>,
+    /// The integer type of an enum serialized as the discriminants of its variants, rather than their names
+    ///
+    /// Such enums derive `Serialize_repr` and `Deserialize_repr` of the `serde_repr` crate instead of the serde traits.
+    pub repr: Option<String>,
+    /// The `x-kubernetes-validations` rules of the container schema
+    pub rules: Vec<ValidationRule>,
     pub supports_derive_default: OnceCell<bool>,
 }
 
 /// Output member belonging to an Container
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Member {
     /// The raw, unsanitized name of the member
     ///
@@ -98,6 +177,70 @@ pub struct Member {
     pub extra_annot: Vec<String>,
     /// Documentation properties extracted from the property
     pub docs: Option<String>,
+    /// The `default` value declared for the property in the schema
+    ///
+    /// This is only kept by `Output::schema_defaults` when defaults are emitted.
+    pub default: Option<serde_json::Value>,
+    /// Value constraints declared for the property in the schema
+    ///
+    /// These are only kept by `Output::constraints` when validation is emitted.
+    pub constraints: Constraints,
+    /// The `x-kubernetes-validations` rules of the property schema
+    ///
+    /// Rules of a property with the type of a generated container are also the rules of that container.
+    pub rules: Vec<ValidationRule>,
+    /// The `x-kubernetes-list-type` of an array property, unless `atomic`
+    ///
+    /// This is only kept by `Output::list_types` when list types are honored.
+    pub list_type: Option<ListType>,
+}
+
+/// How the items of an array are identified when merging it
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListType {
+    /// Items are unique scalars
+    Set,
+    /// Items are objects identified by the values of these `x-kubernetes-list-map-keys`
+    Map(Vec<String>),
+}
+
+/// Value constraints of a schema property, checked by the generated `validate_at`
+///
+/// `required` is not among these, as required members are not wrapped in an `Option`.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Constraints {
+    /// Lower bound of a number
+    pub minimum: Option<f64>,
+    /// Upper bound of a number
+    pub maximum: Option<f64>,
+    /// Whether the `minimum` itself is excluded
+    pub exclusive_minimum: bool,
+    /// Whether the `maximum` itself is excluded
+    pub exclusive_maximum: bool,
+    /// A number must be a multiple of this
+    pub multiple_of: Option<f64>,
+    /// Regular expression a string must match
+    pub pattern: Option<String>,
+    /// Minimum number of characters in a string
+    pub min_length: Option<i64>,
+    /// Maximum number of characters in a string
+    pub max_length: Option<i64>,
+    /// Minimum number of items in an array
+    pub min_items: Option<i64>,
+    /// Maximum number of items in an array
+    pub max_items: Option<i64>,
+    /// Whether the items of an array must be unique
+    pub unique_items: bool,
+    /// Minimum number of entries in a map
+    pub min_properties: Option<i64>,
+    /// Maximum number of entries in a map
+    pub max_properties: Option<i64>,
+}
+
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
 }
 
 impl Container {
@@ -105,16 +248,36 @@ impl Container {
         self.members.iter().any(|m| m.type_.contains("BTreeMap"))
     }
 
+    pub fn uses_btreesets(&self) -> bool {
+        self.members.iter().any(|m| m.type_.contains("BTreeSet"))
+    }
+
     pub fn uses_hashmaps(&self) -> bool {
         self.members.iter().any(|m| m.type_.contains("HashMap"))
     }
 
-    pub fn uses_datetime(&self) -> bool {
-        self.members.iter().any(|m| m.type_.contains("DateTime"))
+    /// Whether a member type uses the `date-time` type of the backend
+    pub fn uses_datetime(&self, backend: DateTimeBackend) -> bool {
+        backend
+            .date_time()
+            .is_some_and(|(type_, _)| self.uses_type(type_name(type_)))
+    }
+
+    /// Whether a member type uses the `date` type of the backend
+    pub fn uses_date(&self, backend: DateTimeBackend) -> bool {
+        backend
+            .date()
+            .is_some_and(|(type_, _)| self.uses_type(type_name(type_)))
+    }
+
+    /// Whether a member tells an explicit null apart from an absent value, through `deserialize_some`
+    pub fn uses_explicit_null(&self) -> bool {
+        self.members.iter().any(|m| m.type_.starts_with("Option<Option<"))
     }
 
-    pub fn uses_date(&self) -> bool {
-        self.members.iter().any(|m| m.type_.contains("NaiveDate"))
+    /// Whether this is an enum serialized as the discriminants of its variants
+    pub fn uses_repr(&self) -> bool {
+        self.repr.is_some()
     }
 
     pub fn uses_int_or_string(&self) -> bool {
@@ -133,12 +296,92 @@ impl Container {
         self.level == 1 && self.name.ends_with("Status")
     }
 
+    /// Whether this is the custom resource, its spec or its status, which are named after the kind
+    pub(crate) fn is_named_after_kind(&self) -> bool {
+        self.is_root() || self.is_main_container() || self.is_status_container()
+    }
+
+    #[deprecated(note = "use `uses_type(\"Condition\")`")]
     pub fn contains_conditions(&self) -> bool {
-        self.members.iter().any(|m| m.type_.contains("Vec<Condition>"))
+        self.uses_type("Condition")
     }
 
+    #[deprecated(note = "use `uses_type(\"ObjectReference\")`")]
     pub fn contains_object_ref(&self) -> bool {
-        self.members.iter().any(|m| m.type_.contains("ObjectReference"))
+        self.uses_type("ObjectReference")
+    }
+
+    /// Whether a member type refers to the type `name`, e.g. `Option<Vec<Toleration>>` to `Toleration`
+    pub fn uses_type(&self, name: &str) -> bool {
+        self.members.iter().any(|m| {
+            m.type_
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .any(|part| part == name)
+        })
+    }
+
+    /// Rename the member with the Rust or schema name `name`, keeping its schema name for serde
+    ///
+    /// Returns whether the container has such a member.
+    pub fn rename_member(&mut self, name: &str, to: &str) -> anyhow::Result<bool> {
+        let Some(i) = self
+            .members
+            .iter()
+            .position(|m| m.name == name || m.original_name() == name)
+        else {
+            return Ok(false);
+        };
+        let to = Container::try_escape_name(to.to_string()).ok_or_else(|| anyhow!("invalid name {}", to))?;
+        if self
+            .members
+            .iter()
+            .enumerate()
+            .any(|(j, m)| j != i && m.name == to)
+        {
+            bail!(
+                "cannot rename {}.{} to {}, as another member has that name",
+                self.name,
+                name,
+                to
+            );
+        }
+
+        let member = &mut self.members[i];
+        let original = member.original_name().to_string();
+        member
+            .serde_annot
+            .retain(|annot| !annot.starts_with("rename = \""));
+        if to != original {
+            member.serde_annot.push(format!("rename = \"{}\"", original));
+        }
+        if self.default_variant.as_ref() == Some(&member.name) {
+            self.default_variant = Some(to.clone());
+        }
+        member.name = to;
+        Ok(true)
+    }
+
+    /// Whether two containers are identical but for their names, docs and levels
+    pub fn same_shape(&self, other: &Container) -> bool {
+        (
+            &self.members,
+            &self.is_enum,
+            &self.serde_annot,
+            &self.default_variant,
+            &self.repr,
+            &self.rules,
+        ) == (
+            &other.members,
+            &other.is_enum,
+            &other.serde_annot,
+            &other.default_variant,
+            &other.repr,
+            &other.rules,
+        )
+    }
+
+    pub fn has_defaults(&self) -> bool {
+        self.members.iter().any(|m| m.default.is_some())
     }
 
     /// Checks if default is implemented for all props, and if not, returns false
@@ -146,10 +389,8 @@ impl Container {
     /// Behavior for --smart-derive-elision.
     pub fn can_derive_default(&self, containers: &[Container]) -> bool {
         if self.is_enum {
-            // Need to drop Default from enum as this cannot be derived.
-            // Enum defaults need to either be manually derived
-            // or we can insert enum defaults
-            return false;
+            // Enums can only derive Default when the schema names a default variant
+            return self.default_variant.is_some();
         }
 
         if let Some(can_derive) = self.supports_derive_default.get() {
@@ -158,9 +399,27 @@ impl Container {
         }
 
         for m in &self.members {
+            // DynamicObject, IP addresses and the date-times of time and k8s-openapi do not implement Default
+            let no_default = [
+                "DynamicObject",
+                "Ipv4Addr",
+                "Ipv6Addr",
+                "OffsetDateTime",
+                "Date",
+                "Time",
+            ];
+            if m.default.is_none()
+                && no_default.contains(&m.type_.as_str())
+                && !containers.iter().any(|c| c.name == m.type_)
+            {
+                self.supports_derive_default.set(false).unwrap();
+                return false;
+            }
             // If the type contains a <, it's a container type. All kopium containers (Map, Vec, Option) has impl Default.
             // If the first character is lowercase, assume it's a built-in type and skip the check.
-            if !m.type_.contains('<')
+            // If the member has a schema default, the manual Default impl uses that instead.
+            if m.default.is_none()
+                && !m.type_.contains('<')
                 && m.type_ != "String"
                 && m.type_ != "IntOrString"
                 && m.type_ != "NaiveDate"
@@ -196,7 +455,10 @@ impl Container {
                 // `mod` -> `Mod`
                 // `301` -> `301` -> `r#301` -> `r#_301`
                 // `!=` -> `!=` -> `r#!=` -> `r#_!=` -> `KopiumVariant{i}`
-                let name = if m.name.is_empty() {
+                let name = if self.repr.is_some() {
+                    // `301` -> `Value301`, `-1` -> `ValueMinus1`
+                    format!("Value{}", m.name.replace('-', "Minus"))
+                } else if m.name.is_empty() {
                     "KopiumEmpty".to_owned()
                 } else if m.name == "-" {
                     "KopiumDash".to_owned()
@@ -225,6 +487,9 @@ impl Container {
             seen.push(new_name.clone());
 
             if new_name != m.name {
+                if self.default_variant.as_ref() == Some(&m.name) {
+                    self.default_variant = Some(new_name.clone());
+                }
                 m.serde_annot.push(format!("rename = \"{}\"", m.name));
                 m.name = new_name;
             }
@@ -233,11 +498,17 @@ impl Container {
 
     /// Add builder annotations
     pub fn builder_fields(&mut self) {
+        if self.is_enum {
+            return; // TypedBuilder does not work with enums
+        }
         for m in &mut self.members {
             if m.type_.starts_with("Option<") {
                 m.extra_annot
                     .push("#[builder(default, setter(strip_option))]".to_string());
-            } else if m.type_.starts_with("Vec<") || m.type_.starts_with("BTreeMap<") {
+            } else if m.type_.starts_with("Vec<")
+                || m.type_.starts_with("BTreeMap<")
+                || m.type_.starts_with("BTreeSet<")
+            {
                 m.extra_annot.push("#[builder(default)]".to_string());
             }
         }
@@ -263,6 +534,267 @@ impl Container {
     }
 }
 
+impl Member {
+    /// The original schema name of the member (before any `Container::rename`)
+    pub fn original_name(&self) -> &str {
+        self.serde_annot
+            .iter()
+            .find_map(|annot| annot.strip_prefix("rename = \"")?.strip_suffix('"'))
+            .unwrap_or(&self.name)
+    }
+
+    /// Name of the generated function returning the schema default of this member
+    pub fn default_fn(&self) -> String {
+        format!("default_{}", self.name.trim_start_matches("r#"))
+    }
+
+    /// The schema default of this member as a rust expression
+    ///
+    /// None when there is no default, or when it cannot be written as a literal of the member type.
+    pub fn default_expr(&self, containers: &[Container]) -> Option<String> {
+        format_default(&self.type_, self.default.as_ref()?, containers)
+    }
+}
+
+fn format_default(type_: &str, value: &serde_json::Value, containers: &[Container]) -> Option<String> {
+    use serde_json::Value;
+
+    let generic = |wrapper: &str| type_.strip_prefix(wrapper)?.strip_suffix('>');
+    if let Some(inner) = generic("Option<") {
+        if value.is_null() {
+            return Some("None".to_string());
+        }
+        return Some(format!("Some({})", format_default(inner, value, containers)?));
+    }
+    if let Some(inner) = generic("Vec<").or_else(|| generic("BTreeSet<")) {
+        let items = value
+            .as_array()?
+            .iter()
+            .map(|v| format_default(inner, v, containers))
+            .collect::<Option<Vec<_>>>()?
+            .join(", ");
+        // a call rather than `vec!`, so that renames reach the type names inside
+        return Some(match generic("Vec<") {
+            Some(_) => format!("Vec::from([{items}])"),
+            None => format!("BTreeSet::from([{items}])"),
+        });
+    }
+    for map in [MapType::BTreeMap, MapType::HashMap] {
+        if let Some(inner) = generic(&format!("{}<String, ", map.name())) {
+            let entries = value
+                .as_object()?
+                .iter()
+                .map(|(k, v)| {
+                    Some(format!(
+                        "({:?}.to_string(), {})",
+                        k,
+                        format_default(inner, v, containers)?
+                    ))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            return Some(format!("{}::from([{}])", map.name(), entries.join(", ")));
+        }
+    }
+
+    match (type_, value) {
+        ("String", Value::String(s)) => Some(format!("{s:?}.to_string()")),
+        ("bool", Value::Bool(b)) => Some(b.to_string()),
+        ("f32" | "f64", Value::Number(n)) => Some(format!("{:?}", n.as_f64()?)),
+        (int, Value::Number(n)) if (int.starts_with('i') || int.starts_with('u')) && !n.is_f64() => {
+            let value = n
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from))?;
+            integer_fits(int, value).then(|| n.to_string())
+        }
+        ("IntOrString", Value::Number(n)) if !n.is_f64() => Some(format!("IntOrString::Int({n})")),
+        ("IntOrString", Value::String(s)) => Some(format!("IntOrString::String({s:?}.to_string())")),
+        ("serde_json::Value", v) => Some(format!("serde_json::json!({v})")),
+        ("Quantity", Value::String(_) | Value::Number(_)) => {
+            let quantity = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            Some(format!("Quantity({quantity:?}.to_string())"))
+        }
+        ("Ipv4Addr", Value::String(s)) => {
+            s.parse::<std::net::Ipv4Addr>().ok()?;
+            Some(format!("{s:?}.parse().unwrap()"))
+        }
+        ("Ipv6Addr", Value::String(s)) => {
+            s.parse::<std::net::Ipv6Addr>().ok()?;
+            Some(format!("{s:?}.parse().unwrap()"))
+        }
+        (name, Value::String(s))
+            if formats::is_newtype(name) && !containers.iter().any(|c| c.name == name) =>
+        {
+            Some(format!("{name}({s:?}.to_string())"))
+        }
+        (name, Value::String(_) | Value::Number(_)) => {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let variant = containers
+                .iter()
+                .find(|c| c.is_enum && c.name == name)?
+                .members
+                .iter()
+                .find(|m| m.type_.is_empty() && m.original_name() == value)?;
+            Some(format!("{}::{}", name, variant.name))
+        }
+        (name, Value::Object(fields)) => {
+            let container = containers.iter().find(|c| !c.is_enum && c.name == name)?;
+            if !fields
+                .keys()
+                .all(|k| container.members.iter().any(|m| m.original_name() == k))
+            {
+                return None;
+            }
+            let members = container
+                .members
+                .iter()
+                .map(|m| {
+                    let expr = match fields.get(m.original_name()) {
+                        Some(v) => format_default(&m.type_, v, containers)?,
+                        None if m.default.is_some() => format!("{}::{}()", name, m.default_fn()),
+                        None if m.serde_annot.iter().any(|a| a == "default") => {
+                            "Default::default()".to_string()
+                        }
+                        // a required member without a default would fail to deserialize
+                        None => return None,
+                    };
+                    Some(format!("{}: {}", m.name, expr))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("{} {{ {} }}", name, members.join(", ")))
+        }
+        _ => None,
+    }
+}
+
+/// Whether an integer value fits an integer type (e.g. `-1` does not fit `u32`)
+pub(crate) fn integer_fits(type_: &str, value: i128) -> bool {
+    match type_ {
+        "i8" => i8::try_from(value).is_ok(),
+        "i16" => i16::try_from(value).is_ok(),
+        "i32" => i32::try_from(value).is_ok(),
+        "i64" => i64::try_from(value).is_ok(),
+        "u8" => u8::try_from(value).is_ok(),
+        "u16" => u16::try_from(value).is_ok(),
+        "u32" => u32::try_from(value).is_ok(),
+        "u64" => u64::try_from(value).is_ok(),
+        "u128" => value >= 0,
+        _ => true,
+    }
+}
+
+/// The name of a possibly generic type, e.g. `DateTime` for `DateTime<Utc>`
+fn type_name(type_: &str) -> &str {
+    type_.split('<').next().unwrap_or(type_)
+}
+
+/// The `BTreeSet` type for a (possibly optional) `Vec` of orderable scalars
+fn set_type(type_: &str) -> Option<String> {
+    if let Some(inner) = type_.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+        return Some(format!("Option<{}>", set_type(inner)?));
+    }
+    let item = type_.strip_prefix("Vec<")?.strip_suffix('>')?;
+    (is_scalar(item) && !item.starts_with('f')).then(|| format!("BTreeSet<{item}>"))
+}
+
+/// Whether a type is a string, number or boolean
+pub(crate) fn is_scalar(type_: &str) -> bool {
+    matches!(
+        type_,
+        "String"
+            | "bool"
+            | "f32"
+            | "f64"
+            | "i8"
+            | "i16"
+            | "i32"
+            | "i64"
+            | "i128"
+            | "u8"
+            | "u16"
+            | "u32"
+            | "u64"
+            | "u128"
+    )
+}
+
+/// The Rust name of a type, without the `Spec` of types below the spec (e.g. `FooBar` for `FooSpecBar`)
+pub(crate) fn rust_name(kind: &str, name: &str) -> String {
+    match name.strip_prefix(&format!("{kind}Spec")) {
+        Some(rest) if rest.starts_with(|c: char| !c.is_lowercase()) => format!("{kind}{rest}"),
+        _ => name.to_string(),
+    }
+}
+
+/// The name of a group of merged containers
+///
+/// This is the camel case words all names start and end with, e.g. `FooSpecTlsConfig` for `FooSpecTlsConfig` and
+/// `FooSpecProxyTlsConfig`. If they only share a prefix, or that name is `taken` by another type, this is the shortest
+/// of the names (the first in alphabetical order among equally short names).
+fn canonical_name(names: &[&str], taken: impl Fn(&str) -> bool) -> String {
+    let words = names.iter().map(|name| camel_words(name)).collect::<Vec<_>>();
+    let shortest = words.iter().map(Vec::len).min().unwrap_or_default();
+    let prefix = (0..shortest)
+        .take_while(|&i| words.iter().all(|w| w[i] == words[0][i]))
+        .count();
+    let suffix = (1..=shortest - prefix)
+        .take_while(|&i| {
+            words
+                .iter()
+                .all(|w| w[w.len() - i] == words[0][words[0].len() - i])
+        })
+        .count();
+
+    let candidate = [&words[0][..prefix], &words[0][words[0].len() - suffix..]]
+        .concat()
+        .concat();
+    if suffix > 0 && !taken(&candidate) {
+        return candidate;
+    }
+    names
+        .iter()
+        .min_by_key(|name| (name.len(), **name))
+        .map(|name| name.to_string())
+        .unwrap_or_default()
+}
+
+/// Split an UpperCamelCase name into its words, e.g. `FooOauth2Tls` into `Foo`, `Oauth2` and `Tls`
+fn camel_words(name: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = 0;
+    for (i, c) in name.char_indices().skip(1) {
+        if c.is_uppercase() {
+            words.push(&name[start..i]);
+            start = i;
+        }
+    }
+    words.push(&name[start..]);
+    words
+}
+
+/// Replace whole type names in a member type, e.g. `Foo` in `Option<Vec<Foo>>` but not in `Option<FooBar>`
+pub(crate) fn replace_type_names(type_: &str, replace: impl Fn(&str) -> Option<String>) -> String {
+    let mut replaced = String::new();
+    let mut rest = type_;
+    while let Some(first) = rest.chars().next() {
+        let end = match rest.find(|c: char| !c.is_alphanumeric() && c != '_') {
+            Some(0) => first.len_utf8(),
+            Some(end) => end,
+            None => rest.len(),
+        };
+        let (part, tail) = rest.split_at(end);
+        replaced.push_str(&replace(part).unwrap_or_else(|| part.to_string()));
+        rest = tail;
+    }
+    replaced
+}
+
 impl Output {
     /// Rename all structs and all their members to rust conventions
     ///
@@ -271,7 +803,7 @@ impl Output {
     ///
     /// It is unsound to skip this step. Some CRDs use kebab-cased members is invalid in Rust.
     pub fn rename(mut self) -> Self {
-        for c in &mut self.0 {
+        for c in &mut self.containers {
             c.rename();
         }
         self
@@ -280,15 +812,391 @@ impl Output {
     /// Add builders to all output members
     ///
     /// Adds #[builder(default, setter(strip_option))] to all option types.
-    /// Adds #[builder(default)] to required vec and btreemaps.
+    /// Adds #[builder(default)] to required vecs, btreemaps and btreesets.
     pub fn builder_fields(mut self, builders: bool) -> Self {
         if builders {
-            for c in &mut self.0 {
+            for c in &mut self.containers {
                 c.builder_fields()
             }
         }
         self
     }
+
+    /// Keep schema defaults on all output members
+    ///
+    /// Defaults are always found by the analyzer, but are dropped here unless they are emitted.
+    /// Defaults that cannot be written as a literal of their member type are dropped with a warning.
+    pub fn schema_defaults(mut self, defaults: bool) -> Self {
+        if !defaults {
+            for c in &mut self.containers {
+                for m in &mut c.members {
+                    m.default = None;
+                }
+            }
+            return self;
+        }
+        // defaults of structs may refer to the defaults of their members, so drop until none are left to drop
+        loop {
+            let unsupported = self
+                .containers
+                .iter()
+                .enumerate()
+                .flat_map(|(i, c)| c.members.iter().enumerate().map(move |(j, m)| (i, j, m)))
+                .filter(|(_, _, m)| m.default.is_some() && m.default_expr(&self.containers).is_none())
+                .map(|(i, j, _)| (i, j))
+                .collect::<Vec<_>>();
+            if unsupported.is_empty() {
+                break;
+            }
+            for (i, j) in unsupported {
+                let c = &mut self.containers[i];
+                let m = &mut c.members[j];
+                let value = m.default.take().unwrap_or_default();
+                let warning = format!(
+                    "not emitting default of {}.{} - {} cannot be written as {}",
+                    c.name,
+                    m.original_name(),
+                    value,
+                    m.type_
+                );
+                self.warn(warning);
+            }
+        }
+        self
+    }
+
+    /// Keep value constraints on all output members
+    ///
+    /// Constraints are always found by the analyzer, but are dropped here unless validation is emitted.
+    /// Patterns that the `regex` crate cannot compile (e.g. lookarounds) are dropped with a warning.
+    pub fn constraints(mut self, validate: bool) -> Self {
+        let names = self
+            .containers
+            .iter()
+            .map(|c| c.name.clone())
+            .collect::<HashSet<_>>();
+        let mut unsupported = vec![];
+        for c in &mut self.containers {
+            for m in &mut c.members {
+                if !validate {
+                    m.constraints = Constraints::default();
+                    continue;
+                }
+                let has_string_constraints = m.constraints.min_length.is_some()
+                    || m.constraints.max_length.is_some()
+                    || m.constraints.pattern.is_some();
+                let mut scalar = m.type_.as_str();
+                while let Some(inner) = scalar.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+                    scalar = inner;
+                }
+                // strings typed by their format cannot be checked, other than the generated newtypes of strings
+                if has_string_constraints
+                    && !is_scalar(scalar)
+                    && !formats::is_newtype(scalar)
+                    && !names.contains(scalar)
+                    && scalar != "IntOrString"
+                {
+                    unsupported.push(format!(
+                        "not validating length or pattern of {}.{} - {} is not a string",
+                        c.name,
+                        m.original_name(),
+                        scalar
+                    ));
+                    m.constraints.min_length = None;
+                    m.constraints.max_length = None;
+                    m.constraints.pattern = None;
+                } else if let Some(pattern) = &m.constraints.pattern {
+                    if let Err(err) = Regex::new(pattern) {
+                        unsupported.push(format!(
+                            "not validating pattern of {}.{} - {}",
+                            c.name,
+                            m.original_name(),
+                            err.to_string().lines().last().unwrap_or_default()
+                        ));
+                        m.constraints.pattern = None;
+                    }
+                }
+            }
+        }
+        for warning in unsupported {
+            self.warn(warning);
+        }
+        self
+    }
+
+    /// Honor the `x-kubernetes-list-type` of all output members
+    ///
+    /// List types are always found by the analyzer, but are dropped here unless they are honored. Sets of orderable
+    /// scalars become `BTreeSet`s. Map lists are only kept when their items are structs with scalar keys, which the
+    /// generated accessors can compare, and dropped with a warning otherwise.
+    pub fn list_types(mut self, honor: bool) -> Self {
+        if !honor {
+            for c in &mut self.containers {
+                for m in &mut c.members {
+                    m.list_type = None;
+                }
+            }
+            return self;
+        }
+
+        let mut unsupported = vec![];
+        for (i, c) in self.containers.iter().enumerate() {
+            for (j, m) in c.members.iter().enumerate() {
+                if let Some(ListType::Map(keys)) = &m.list_type {
+                    if let Err(reason) = self.check_map_list(&m.type_, keys) {
+                        let warning = format!(
+                            "not generating accessors for {}.{} - {}",
+                            c.name,
+                            m.original_name(),
+                            reason
+                        );
+                        unsupported.push((i, j, warning));
+                    }
+                }
+            }
+        }
+        for (i, j, warning) in unsupported {
+            self.containers[i].members[j].list_type = None;
+            self.warn(warning);
+        }
+
+        for c in &mut self.containers {
+            for m in &mut c.members {
+                if m.list_type == Some(ListType::Set) {
+                    if let Some(set) = set_type(&m.type_) {
+                        m.type_ = set;
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Check that a map list holds generated structs with scalar members for all keys
+    fn check_map_list(&self, type_: &str, keys: &[String]) -> Result<(), String> {
+        fn generic<'a>(type_: &'a str, wrapper: &str) -> Option<&'a str> {
+            type_.strip_prefix(wrapper)?.strip_suffix('>')
+        }
+        let list = generic(type_, "Option<").unwrap_or(type_);
+        let Some(item) = generic(list, "Vec<") else {
+            return Err(format!("{type_} is not a list"));
+        };
+        let Some(item) = self.containers.iter().find(|c| c.name == item && !c.is_enum) else {
+            return Err(format!("{item} is not a generated struct"));
+        };
+        if keys.is_empty() {
+            return Err("no x-kubernetes-list-map-keys are declared".to_string());
+        }
+        for key in keys {
+            let Some(member) = item.members.iter().find(|m| m.original_name() == key) else {
+                return Err(format!("key {key} is not a property of {}", item.name));
+            };
+            if !is_scalar(generic(&member.type_, "Option<").unwrap_or(&member.type_)) {
+                return Err(format!("key {key} is not a string, number or boolean"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize date-times of the `time` backend as RFC 3339
+    ///
+    /// `time` only has serde helpers for fields and options, so date-times in lists and maps are kept as strings,
+    /// with a warning.
+    pub fn datetime(mut self, backend: DateTimeBackend) -> Self {
+        if backend != DateTimeBackend::Time {
+            return self;
+        }
+        let mut unsupported = vec![];
+        for c in &mut self.containers {
+            for m in &mut c.members {
+                match m.type_.as_str() {
+                    "OffsetDateTime" => m.serde_annot.push(r#"with = "time::serde::rfc3339""#.to_string()),
+                    "Option<OffsetDateTime>" => m
+                        .serde_annot
+                        .push(r#"with = "time::serde::rfc3339::option""#.to_string()),
+                    type_ if type_.contains("OffsetDateTime") => {
+                        unsupported.push(format!(
+                            "not typing {}.{} as OffsetDateTime - only fields and options can be",
+                            c.name,
+                            m.original_name()
+                        ));
+                        m.type_ = replace_type_names(type_, |name| {
+                            (name == "OffsetDateTime").then(|| "String".to_string())
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for warning in unsupported {
+            self.warn(warning);
+        }
+        self
+    }
+
+    /// Rename containers by a naming strategy
+    ///
+    /// Containers are named after their path in the schema by the analyzer. Other strategies rename them here, along
+    /// with all member types referring to them.
+    pub fn naming(mut self, strategy: NamingStrategy) -> Self {
+        let renames = naming::type_names(&self.containers, strategy);
+        for c in &mut self.containers {
+            if let Some(rename) = renames.get(&c.name) {
+                c.name = rename.clone();
+            }
+            for m in &mut c.members {
+                m.type_ = replace_type_names(&m.type_, |name| renames.get(name).cloned());
+            }
+        }
+        self
+    }
+
+    /// Apply renames of types, fields and enum variants
+    ///
+    /// Member types referring to renamed types are rewritten, and renamed members keep their schema names through
+    /// serde renames. Renames that do not match anything are warned about.
+    pub fn renames(mut self, renames: &[Rename]) -> anyhow::Result<Self> {
+        let Some(kind) = self
+            .containers
+            .iter()
+            .find(|c| c.level == 0)
+            .map(|c| c.name.clone())
+        else {
+            return Ok(self);
+        };
+        let is_target = |c: &Container, target: &str| c.name == target || rust_name(&kind, &c.name) == target;
+
+        let mut unmatched = vec![];
+        // members are renamed first, as their targets name types before renaming
+        for rename in renames {
+            let Some(member) = rename.member() else { continue };
+            let mut found = false;
+            for c in self
+                .containers
+                .iter_mut()
+                .filter(|c| is_target(c, rename.type_name()))
+            {
+                found |= c.rename_member(member, &rename.to)?;
+            }
+            if !found {
+                unmatched.push(rename);
+            }
+        }
+
+        let mut types = HashMap::new();
+        for rename in renames.iter().filter(|rename| rename.member().is_none()) {
+            let Some(c) = self.containers.iter().find(|c| is_target(c, &rename.target)) else {
+                unmatched.push(rename);
+                continue;
+            };
+            if c.is_named_after_kind() {
+                bail!(
+                    "cannot rename {}, as the custom resource, spec and status are named after the kind",
+                    rename.target
+                );
+            }
+            types.insert(c.name.clone(), rename.to.clone());
+        }
+        for c in &mut self.containers {
+            if let Some(to) = types.get(&c.name) {
+                c.name = to.clone();
+            }
+            for m in &mut c.members {
+                m.type_ = replace_type_names(&m.type_, |name| types.get(name).cloned());
+            }
+        }
+        for to in types.values() {
+            if self
+                .containers
+                .iter()
+                .filter(|c| rust_name(&kind, &c.name) == *to)
+                .count()
+                > 1
+            {
+                bail!("cannot rename a type to {}, as another type has that name", to);
+            }
+        }
+
+        for rename in unmatched {
+            self.warn(format!(
+                "rename of {} did not match any type or member",
+                rename.target
+            ));
+        }
+        Ok(self)
+    }
+
+    /// Merge structurally identical containers into one canonical container
+    ///
+    /// Containers with the same members and representation, but different names, are replaced by the first of them,
+    /// named by [`canonical_name`], and member types referring to the others are rewritten. Docs are only kept when
+    /// all merged containers have the same docs. Merging can make their
+    /// parents identical, so this repeats until nothing is merged. The root, spec and status are never merged.
+    pub fn dedup(mut self, dedup: bool) -> Self {
+        if !dedup {
+            return self;
+        }
+        let kind = match self.containers.iter().find(|c| c.level == 0) {
+            Some(root) => root.name.clone(),
+            None => return self,
+        };
+        loop {
+            let mut groups: Vec<Vec<usize>> = vec![];
+            for (i, c) in self.containers.iter().enumerate() {
+                if c.is_named_after_kind() {
+                    continue;
+                }
+                match groups.iter_mut().find(|g| self.containers[g[0]].same_shape(c)) {
+                    Some(group) => group.push(i),
+                    None => groups.push(vec![i]),
+                }
+            }
+
+            let mut renames: HashMap<String, Container> = HashMap::new();
+            for group in groups.into_iter().filter(|g| g.len() > 1) {
+                let names = group
+                    .iter()
+                    .map(|&i| self.containers[i].name.as_str())
+                    .collect::<Vec<_>>();
+                let taken = |name: &str| {
+                    self.containers
+                        .iter()
+                        .chain(renames.values())
+                        .filter(|c| !names.contains(&c.name.as_str()))
+                        .any(|c| c.name == name || rust_name(&kind, &c.name) == rust_name(&kind, name))
+                };
+                let mut canonical = self.containers[group[0]].clone();
+                canonical.name = canonical_name(&names, taken);
+                if group.iter().any(|&i| self.containers[i].docs != canonical.docs) {
+                    canonical.docs = None;
+                }
+                for name in names {
+                    renames.insert(name.to_string(), canonical.clone());
+                }
+            }
+            if renames.is_empty() {
+                break;
+            }
+
+            let mut kept = HashSet::new();
+            for c in std::mem::take(&mut self.containers) {
+                match renames.get(&c.name) {
+                    Some(canonical) => {
+                        if kept.insert(canonical.name.clone()) {
+                            self.containers.push(canonical.clone());
+                        }
+                    }
+                    None => self.containers.push(c),
+                }
+            }
+            for c in &mut self.containers {
+                for m in &mut c.members {
+                    m.type_ = replace_type_names(&m.type_, |name| renames.get(name).map(|c| c.name.clone()));
+                }
+            }
+        }
+        self
+    }
 }
 
 /// Type used for additionalProperties maps
@@ -319,7 +1227,99 @@ impl MapType {
     }
 }
 
+/// Type used for properties marked with `x-kubernetes-embedded-resource`
+#[derive(
+    // std
+    Eq,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    // strum
+    strum::Display,
+    strum::AsRefStr,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum EmbeddedResources {
+    /// Use `kube::core::DynamicObject`, holding any kind of resource
+    #[default]
+    Dynamic,
+    /// Generate a type from the schema, with `ObjectMeta` as its `metadata`
+    Generated,
+}
+
+/// Types used for strings with a `date-time` or `date` format
+#[derive(
+    // std
+    Eq,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    // strum
+    strum::Display,
+    strum::AsRefStr,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+pub enum DateTimeBackend {
+    /// Keep them as `String`
+    #[default]
+    String,
+    /// Use `chrono::DateTime<Utc>` and `chrono::NaiveDate`
+    Chrono,
+    /// Use `jiff::Timestamp` and `jiff::civil::Date`
+    Jiff,
+    /// Use `time::OffsetDateTime` (through `time::serde::rfc3339`) and `time::Date`
+    Time,
+    /// Use the `Time` of k8s_openapi, and `String` for dates
+    K8s,
+}
+
+impl DateTimeBackend {
+    /// The backend for schemas of the non-standard `date` type, which have always used chrono
+    pub(crate) fn or_chrono(self) -> Self {
+        match self {
+            DateTimeBackend::String => DateTimeBackend::Chrono,
+            backend => backend,
+        }
+    }
+
+    /// The type of `date-time` strings, along with its import
+    pub(crate) fn date_time(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            DateTimeBackend::String => None,
+            DateTimeBackend::Chrono => Some(("DateTime<Utc>", "chrono::{DateTime, Utc}")),
+            DateTimeBackend::Jiff => Some(("Timestamp", "jiff::Timestamp")),
+            DateTimeBackend::Time => Some(("OffsetDateTime", "time::OffsetDateTime")),
+            DateTimeBackend::K8s => Some(("Time", "k8s_openapi::apimachinery::pkg::apis::meta::v1::Time")),
+        }
+    }
+
+    /// The type of `date` strings, along with its import
+    pub(crate) fn date(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            DateTimeBackend::String | DateTimeBackend::K8s => None,
+            DateTimeBackend::Chrono => Some(("NaiveDate", "chrono::naive::NaiveDate")),
+            DateTimeBackend::Jiff => Some(("Date", "jiff::civil::Date")),
+            DateTimeBackend::Time => Some(("Date", "time::Date")),
+        }
+    }
+}
+
 pub fn format_docstr(indent: &str, input: &str) -> String {
+    docstr_lines(input)
+        .iter()
+        .map(|line| format!("{}/// {}", indent, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Clean up a description for use as documentation, returning its lines
+pub(crate) fn docstr_lines(input: &str) -> Vec<String> {
     static RE_CODEBLOCK: OnceLock<Regex> = OnceLock::new();
     let re = RE_CODEBLOCK.get_or_init(|| {
         RegexBuilder::new(r"```.*\n([\s\S]+)\n```")
@@ -334,11 +1334,7 @@ pub fn format_docstr(indent: &str, input: &str) -> String {
     let re_url = RE_URL.get_or_init(|| Regex::new(r"(https?://\S+)").unwrap());
     let linked_input = re_url.replace_all(&cleaned_input, "<$1>");
 
-    format!(
-        "{}/// {}",
-        indent,
-        linked_input.replace('\n', &format!("\n{}/// ", indent))
-    )
+    linked_input.split('\n').map(String::from).collect()
 }
 
 pub fn format_selectable(
@@ -358,8 +1354,9 @@ pub fn format_selectable(
 mod test {
     use crate::format_selectable;
     use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::SelectableField;
+    use serde_json::json;
 
-    use super::{format_docstr, Container, Member};
+    use super::{canonical_name, format_docstr, replace_type_names, Container, Member};
     fn name_only_enum_member(name: &str) -> Member {
         Member {
             name: name.to_string(),
@@ -367,6 +1364,10 @@ mod test {
             serde_annot: vec![],
             extra_annot: vec![],
             docs: None,
+            default: None,
+            constraints: Default::default(),
+            rules: vec![],
+            list_type: None,
         }
     }
     fn name_only_int_member(name: &str) -> Member {
@@ -376,6 +1377,10 @@ mod test {
             serde_annot: vec![],
             extra_annot: vec![],
             docs: None,
+            default: None,
+            constraints: Default::default(),
+            rules: vec![],
+            list_type: None,
         }
     }
 
@@ -503,6 +1508,35 @@ mod test {
                 }],
                 ..Container::default()
             },
+            Container {
+                name: "EnumWithDefault".to_string(),
+                level: 1,
+                is_enum: true,
+                members: vec![name_only_enum_member("A")],
+                default_variant: Some("A".to_string()),
+                ..Container::default()
+            },
+            Container {
+                name: "ReferencesEnumWithDefaultVariant".to_string(),
+                level: 1,
+                members: vec![Member {
+                    name: "enum".to_string(),
+                    type_: "EnumWithDefault".to_string(),
+                    ..Member::default()
+                }],
+                ..Container::default()
+            },
+            Container {
+                name: "ReferencesEnumWithDefault".to_string(),
+                level: 1,
+                members: vec![Member {
+                    name: "enum".to_string(),
+                    type_: "Enum".to_string(),
+                    default: Some(json!("A")),
+                    ..Member::default()
+                }],
+                ..Container::default()
+            },
         ];
         assert!(containers[0].can_derive_default(&containers)); // Simple
         assert!(!containers[1].can_derive_default(&containers)); // Enum
@@ -512,6 +1546,118 @@ mod test {
         assert!(containers[5].can_derive_default(&containers)); // ReferencesEnumOption
         assert!(containers[6].can_derive_default(&containers)); // ReferencesEnumVec
         assert!(containers[7].can_derive_default(&containers)); // ReferencesEnumNestedOption
+        assert!(containers[8].can_derive_default(&containers)); // EnumWithDefault
+        assert!(containers[9].can_derive_default(&containers)); // ReferencesEnumWithDefaultVariant
+        assert!(containers[10].can_derive_default(&containers)); // ReferencesEnumWithDefault
+    }
+
+    #[test]
+    fn formats_schema_defaults() {
+        let containers = vec![
+            Container {
+                name: "Protocol".to_string(),
+                level: 1,
+                members: vec![name_only_enum_member("TCP"), Member {
+                    name: "Http".to_string(),
+                    serde_annot: vec![r#"rename = "http""#.to_string()],
+                    ..Member::default()
+                }],
+                is_enum: true,
+                ..Container::default()
+            },
+            Container {
+                name: "Nested".to_string(),
+                level: 1,
+                members: vec![
+                    Member {
+                        name: "port".to_string(),
+                        type_: "i32".to_string(),
+                        ..Member::default()
+                    },
+                    Member {
+                        name: "protocol".to_string(),
+                        type_: "Option<Protocol>".to_string(),
+                        serde_annot: vec!["default".to_string()],
+                        default: Some(json!("TCP")),
+                        ..Member::default()
+                    },
+                    Member {
+                        name: "tls".to_string(),
+                        type_: "Option<bool>".to_string(),
+                        serde_annot: vec!["default".to_string()],
+                        ..Member::default()
+                    },
+                ],
+                ..Container::default()
+            },
+        ];
+        let expr = |type_: &str, value: serde_json::Value| {
+            Member {
+                type_: type_.to_string(),
+                default: Some(value),
+                ..Member::default()
+            }
+            .default_expr(&containers)
+            .unwrap()
+        };
+
+        assert_eq!(expr("String", json!("a\"b")), r#""a\"b".to_string()"#);
+        assert_eq!(expr("Option<bool>", json!(true)), "Some(true)");
+        assert_eq!(expr("i32", json!(-3)), "-3");
+        assert_eq!(expr("f64", json!(1)), "1.0");
+        assert_eq!(expr("Vec<u16>", json!([1, 2])), "Vec::from([1, 2])");
+        assert_eq!(
+            expr("BTreeMap<String, String>", json!({"a": "b"})),
+            r#"BTreeMap::from([("a".to_string(), "b".to_string())])"#
+        );
+        assert_eq!(expr("IntOrString", json!(80)), "IntOrString::Int(80)");
+        assert_eq!(expr("Option<Protocol>", json!("http")), "Some(Protocol::Http)");
+        assert_eq!(
+            expr("serde_json::Value", json!({"a": 1})),
+            r#"serde_json::json!({"a":1})"#
+        );
+        assert_eq!(
+            expr("Option<BTreeSet<i64>>", json!([2, 1])),
+            "Some(BTreeSet::from([2, 1]))"
+        );
+        assert_eq!(expr("Option<String>", json!(null)), "None");
+        assert_eq!(
+            expr("Option<Quantity>", json!("1Gi")),
+            r#"Some(Quantity("1Gi".to_string()))"#
+        );
+        assert_eq!(expr("Quantity", json!(2)), r#"Quantity("2".to_string())"#);
+        assert_eq!(expr("Duration", json!("30s")), r#"Duration("30s".to_string())"#);
+        assert_eq!(
+            expr("Vec<Ipv4Addr>", json!(["10.0.0.1"])),
+            r#"Vec::from(["10.0.0.1".parse().unwrap()])"#
+        );
+        assert_eq!(expr("Ipv6Addr", json!("::1")), r#""::1".parse().unwrap()"#);
+        assert_eq!(
+            expr("Option<Nested>", json!({"port": 80})),
+            "Some(Nested { port: 80, protocol: Nested::default_protocol(), tls: Default::default() })"
+        );
+
+        let unsupported = |type_: &str, value: serde_json::Value| {
+            Member {
+                type_: type_.to_string(),
+                default: Some(value),
+                ..Member::default()
+            }
+            .default_expr(&containers)
+        };
+        assert_eq!(unsupported("Option<Nested>", json!({"tls": true})), None); // required port missing
+        assert_eq!(
+            unsupported("Option<Nested>", json!({"port": 80, "extra": 1})),
+            None
+        );
+        assert_eq!(unsupported("Option<Missing>", json!({"a": 1})), None);
+        assert_eq!(unsupported("i32", json!("80")), None);
+        assert_eq!(unsupported("Ipv4Addr", json!("10.0.0")), None);
+        assert_eq!(unsupported("ByteString", json!("aGk=")), None);
+        assert_eq!(unsupported("Option<u32>", json!(-1)), None);
+        assert_eq!(unsupported("i32", json!(3000000000u64)), None);
+        assert_eq!(unsupported("Vec<u8>", json!([1, 256])), None);
+        assert_eq!(expr("u64", json!(u64::MAX)), u64::MAX.to_string());
     }
 
     #[test]
@@ -597,4 +1743,41 @@ mod test {
             output
         );
     }
+
+    #[test]
+    fn names_merged_containers() {
+        let free = |_: &str| false;
+        assert_eq!(
+            canonical_name(
+                &["FooSpecEndpointsTlsConfig", "FooSpecProxyOauth2TlsConfig"],
+                free
+            ),
+            "FooSpecTlsConfig"
+        );
+        assert_eq!(
+            canonical_name(&["FooSpecTlsConfig", "FooSpecProxyTlsConfig"], free),
+            "FooSpecTlsConfig"
+        );
+        assert_eq!(
+            canonical_name(&["FooStatusTlsConfig", "FooSpecTlsConfig"], free),
+            "FooTlsConfig"
+        );
+        // names only sharing a prefix, or a taken name, fall back to the shortest name
+        assert_eq!(canonical_name(&["FooSpecBar", "FooSpecBarX"], free), "FooSpecBar");
+        assert_eq!(
+            canonical_name(&["FooSpecProxyTlsConfig", "FooSpecRemoteTlsConfig"], |name| name
+                == "FooSpecTlsConfig"),
+            "FooSpecProxyTlsConfig"
+        );
+    }
+
+    #[test]
+    fn replaces_whole_type_names() {
+        let replace = |name: &str| (name == "FooBar").then(|| "FooBaz".to_string());
+        assert_eq!(
+            replace_type_names("Option<BTreeMap<String, Vec<FooBar>>>", replace),
+            "Option<BTreeMap<String, Vec<FooBaz>>>"
+        );
+        assert_eq!(replace_type_names("Option<FooBarX>", replace), "Option<FooBarX>");
+    }
 }
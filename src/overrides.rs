@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use heck::ToUpperCamelCase;
+
+/// A user-defined type to use for a property, rather than generating one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeOverride {
+    /// JSON path of the property (e.g. `spec.template`), or name of the generated type (e.g. `FooConfig`).
+    pub target: String,
+    /// Path of the type to use (e.g. `my_crate::Config`).
+    pub path: String,
+}
+
+impl TypeOverride {
+    /// Use the type at `path` for `target`.
+    pub fn new(target: impl Into<String>, path: impl Into<String>) -> Self {
+        TypeOverride {
+            target: target.into(),
+            path: path.into(),
+        }
+    }
+
+    /// Name of the type in generated code; the last segment of a plain path, or the whole (generic) path.
+    pub fn type_name(&self) -> &str {
+        path_type_name(&self.path)
+    }
+
+    /// The path to re-export from the prelude, if the type needs an import.
+    pub fn import(&self) -> Option<&str> {
+        path_import(&self.path)
+    }
+
+    /// Names of the generated types of a resource of `kind` the target may refer to.
+    ///
+    /// JSON paths name the type generated for the property, while type names may be given with or without the
+    /// `Spec` that is dropped from the names of the types nested below the spec.
+    pub(crate) fn generated_names(&self, kind: &str) -> Vec<String> {
+        let kind = kind.to_upper_camel_case();
+        if self.target.contains('.') || self.target.starts_with(|c: char| c.is_lowercase()) {
+            let path = self.target.split('.').map(|key| key.to_upper_camel_case());
+            return vec![std::iter::once(kind).chain(path).collect()];
+        }
+
+        let mut names = vec![self.target.clone()];
+        if let Some(rest) = self.target.strip_prefix(&kind).filter(|rest| !rest.is_empty()) {
+            names.push(format!("{}Spec{}", kind, rest));
+        }
+        names
+    }
+}
+
+impl FromStr for TypeOverride {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (target, path) = split_path_assignment(value, "type override", "type override target")?;
+        Ok(TypeOverride::new(target, path))
+    }
+}
+
+/// Name of the type at `path` in generated code; the last segment of a plain path, or the whole (generic) path
+pub(crate) fn path_type_name(path: &str) -> &str {
+    if path.contains('<') {
+        path
+    } else {
+        path.rsplit("::").next().unwrap_or(path)
+    }
+}
+
+/// The path to re-export from the prelude for the type at `path`, if it needs an import
+pub(crate) fn path_import(path: &str) -> Option<&str> {
+    (path.contains("::") && !path.contains('<')).then_some(path)
+}
+
+/// Split a `<KEY>=<PATH>` argument of `what` (e.g. a type override), where neither side may be empty
+///
+/// `key` names the left side in errors, and its last word is the placeholder for it (e.g. `type override target`).
+pub(crate) fn split_path_assignment<'a>(
+    value: &'a str,
+    what: &str,
+    key: &str,
+) -> anyhow::Result<(&'a str, &'a str)> {
+    let Some((left, path)) = value.split_once('=') else {
+        let placeholder = key.rsplit(' ').next().unwrap_or(key).to_uppercase();
+        return Err(anyhow!("{what} must be <{placeholder}>=<PATH> in '{value}'"));
+    };
+
+    if left.is_empty() {
+        return Err(anyhow!("{key} cannot be empty in '{value}'"));
+    }
+
+    if path.is_empty() {
+        return Err(anyhow!("{what} path cannot be empty in '{value}'"));
+    }
+
+    Ok((left, path))
+}
+
+#[cfg(test)]
+#[test]
+fn test_type_override_parsing() {
+    let template = "spec.template=k8s_openapi::api::core::v1::PodTemplateSpec"
+        .parse::<TypeOverride>()
+        .unwrap();
+    assert_eq!(
+        template,
+        TypeOverride::new("spec.template", "k8s_openapi::api::core::v1::PodTemplateSpec")
+    );
+    assert_eq!(template.type_name(), "PodTemplateSpec");
+    assert_eq!(
+        template.import(),
+        Some("k8s_openapi::api::core::v1::PodTemplateSpec")
+    );
+
+    let labels = "FooLabels=BTreeMap<String, String>"
+        .parse::<TypeOverride>()
+        .unwrap();
+    assert_eq!(labels.type_name(), "BTreeMap<String, String>");
+    assert_eq!(labels.import(), None);
+
+    assert_eq!(
+        "spec.template".parse::<TypeOverride>().unwrap_err().to_string(),
+        "type override must be <TARGET>=<PATH> in 'spec.template'"
+    );
+    assert_eq!(
+        "=Config".parse::<TypeOverride>().unwrap_err().to_string(),
+        "type override target cannot be empty in '=Config'"
+    );
+    assert_eq!(
+        "spec.config=".parse::<TypeOverride>().unwrap_err().to_string(),
+        "type override path cannot be empty in 'spec.config='"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_type_override_targets() {
+    let path = TypeOverride::new("spec.endpoints.tlsConfig", "my_crate::TlsConfig");
+    assert_eq!(path.generated_names("Proxy"), vec!["ProxySpecEndpointsTlsConfig"]);
+
+    let rendered = TypeOverride::new("ProxyEndpoints", "my_crate::Endpoint");
+    assert_eq!(rendered.generated_names("Proxy"), vec![
+        "ProxyEndpoints",
+        "ProxySpecEndpoints"
+    ]);
+
+    let other = TypeOverride::new("Endpoint", "my_crate::Endpoint");
+    assert_eq!(other.generated_names("Proxy"), vec!["Endpoint"]);
+}
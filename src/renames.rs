@@ -0,0 +1,184 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// A new name for a generated type, field or enum variant
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rename {
+    /// The type (e.g. `FooConfig`), field (e.g. `FooConfig.tlsConfig`) or variant (e.g. `FooMode::Http`) to rename.
+    ///
+    /// Types may be given by their generated name with or without the `Spec` dropped from the types below the spec,
+    /// and members by their Rust or schema name.
+    pub target: String,
+    /// The new name (e.g. `Config`, `tls` or `Plain`).
+    pub to: String,
+}
+
+impl Rename {
+    /// Rename `target` to `to`.
+    pub fn new(target: impl Into<String>, to: impl Into<String>) -> Self {
+        Rename {
+            target: target.into(),
+            to: to.into(),
+        }
+    }
+
+    /// The type that is renamed, or that has the renamed member.
+    pub fn type_name(&self) -> &str {
+        self.split().0
+    }
+
+    /// The field or variant that is renamed, if not a type.
+    pub fn member(&self) -> Option<&str> {
+        self.split().1
+    }
+
+    fn split(&self) -> (&str, Option<&str>) {
+        if let Some((type_name, variant)) = self.target.split_once("::") {
+            (type_name, Some(variant))
+        } else if let Some((type_name, field)) = self.target.split_once('.') {
+            (type_name, Some(field))
+        } else {
+            (&self.target, None)
+        }
+    }
+}
+
+impl FromStr for Rename {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((target, to)) = value.split_once('=') else {
+            return Err(anyhow!("rename must be <TARGET>=<NAME> in '{value}'"));
+        };
+
+        let rename = Rename::new(target, to);
+        if rename.type_name().is_empty() || rename.member().is_some_and(str::is_empty) {
+            return Err(anyhow!(
+                "rename target must be Type, Type.field or Type::Variant in '{value}'"
+            ));
+        }
+
+        // fields may be keywords, which are escaped, but types and variants cannot
+        let is_ident = to.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && to.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let is_field = rename.member().is_some() && !target.contains("::");
+        if !is_ident || (!is_field && syn::parse_str::<syn::Ident>(to).is_err()) {
+            return Err(anyhow!("rename to '{to}' is not a valid name in '{value}'"));
+        }
+
+        Ok(rename)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_rename_parsing() {
+    let type_name = "FooConfig=Config".parse::<Rename>().unwrap();
+    assert_eq!(type_name, Rename::new("FooConfig", "Config"));
+    assert_eq!(type_name.type_name(), "FooConfig");
+    assert_eq!(type_name.member(), None);
+
+    let field = "FooConfig.tlsConfig=tls".parse::<Rename>().unwrap();
+    assert_eq!(field.type_name(), "FooConfig");
+    assert_eq!(field.member(), Some("tlsConfig"));
+    assert!("FooConfig.kind=type".parse::<Rename>().is_ok());
+
+    let variant = "FooMode::Http=Plain".parse::<Rename>().unwrap();
+    assert_eq!(variant.type_name(), "FooMode");
+    assert_eq!(variant.member(), Some("Http"));
+
+    assert_eq!(
+        "FooConfig".parse::<Rename>().unwrap_err().to_string(),
+        "rename must be <TARGET>=<NAME> in 'FooConfig'"
+    );
+    assert_eq!(
+        "FooConfig.=tls".parse::<Rename>().unwrap_err().to_string(),
+        "rename target must be Type, Type.field or Type::Variant in 'FooConfig.=tls'"
+    );
+    assert_eq!(
+        "FooMode::Http=Self".parse::<Rename>().unwrap_err().to_string(),
+        "rename to 'Self' is not a valid name in 'FooMode::Http=Self'"
+    );
+    assert_eq!(
+        "FooConfig=my-config".parse::<Rename>().unwrap_err().to_string(),
+        "rename to 'my-config' is not a valid name in 'FooConfig=my-config'"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_renames() {
+    use crate::analyzer::{analyze, Config};
+
+    let schema: serde_json::Value = serde_yaml::from_str(
+        r#"
+        properties:
+          spec:
+            properties:
+              protocol:
+                enum:
+                - tcp
+                - udp
+                type: string
+              tlsConfig:
+                properties:
+                  mode:
+                    enum:
+                    - http
+                    - https
+                    type: string
+                  serverName:
+                    type: string
+                type: object
+            type: object
+        type: object
+        "#,
+    )
+    .unwrap();
+    let analyzed = || {
+        let schema = serde_json::from_value(schema.clone()).unwrap();
+        analyze(schema, "Foo", Config::default()).unwrap().rename()
+    };
+    // members are found by the generated name of their type
+    let output = analyzed()
+        .renames(&[
+            Rename::new("FooTlsConfig", "Tls"),
+            Rename::new("FooSpec.tlsConfig", "tls"),
+            Rename::new("Tls.server_name", "sni"),
+            Rename::new("FooSpecTlsConfigMode::Http", "Plain"),
+            Rename::new("FooMissing", "Missing"),
+            Rename::new("FooProtocol", "Protocol"),
+        ])
+        .unwrap();
+    assert_eq!(output.warnings(), [
+        "rename of Tls.server_name did not match any type or member",
+        "rename of FooMissing did not match any type or member"
+    ]);
+
+    let containers = output.output();
+    let spec = &containers[1];
+    assert_eq!(spec.members[0].type_, "Option<Protocol>");
+    assert_eq!(spec.members[1].name, "tls");
+    assert_eq!(spec.members[1].type_, "Option<Tls>");
+    assert_eq!(
+        spec.members[1].serde_annot.last().unwrap(),
+        r#"rename = "tlsConfig""#
+    );
+
+    // enums under the spec are not named after the kind
+    assert_eq!(containers[2].name, "Protocol");
+
+    let tls = &containers[3];
+    assert_eq!(tls.name, "Tls");
+    assert_eq!(tls.members[1].name, "server_name");
+
+    let mode = &containers[4];
+    assert_eq!(mode.members[0].name, "Plain");
+    assert_eq!(mode.members[0].serde_annot, [r#"rename = "http""#]);
+
+    let spec = Rename::new("FooSpec", "Spec");
+    assert!(analyzed().renames(&[spec]).is_err());
+    let clash = Rename::new("FooSpecTlsConfigMode", "FooTlsConfig");
+    assert!(analyzed().renames(&[clash]).is_err());
+}
@@ -0,0 +1,447 @@
+//! Renders generated types into Rust source through `syn` items and `prettyplease`
+use std::collections::HashMap;
+
+use anyhow::Context;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::ValidationRule;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::visit_mut::VisitMut;
+
+use crate::{
+    formats, lists,
+    output::{docstr_lines, replace_type_names},
+    schema, validate, Container, DateTimeBackend, GeneratedTypes, SchemaMode, TypeGenerator,
+};
+
+impl TypeGenerator {
+    /// Render generated types into Rust source
+    ///
+    /// The output starts with the prelude (unless hidden), but has no generation warning.
+    pub fn render(&self, types: &GeneratedTypes) -> anyhow::Result<String> {
+        self.render_filtered(types, |_| true)
+    }
+
+    /// Render the generated types for which `filter` returns `true` into Rust source
+    ///
+    /// The prelude always contains the imports for all generated types.
+    pub fn render_filtered(
+        &self,
+        types: &GeneratedTypes,
+        filter: impl Fn(&Container) -> bool,
+    ) -> anyhow::Result<String> {
+        let mut items = vec![];
+
+        if !self.hide_prelude {
+            items.extend(self.prelude_items(&types.imports)?);
+        }
+
+        let mut evaluates_rules = false;
+        let mut embeds_schema = false;
+        let rendered = types
+            .types()
+            .filter(|container| filter(container))
+            .collect::<Vec<_>>();
+        for &container in &rendered {
+            items.extend(self.container_items(types, container)?);
+            if self.list_types {
+                items.extend(lists::list_map_items(types, container)?);
+            }
+            if self.schema_mode == SchemaMode::Preserved {
+                items.extend(schema::schema_items(types, container, !self.hide_kube)?);
+                embeds_schema |= schema::embeds_schema(container);
+            }
+            if self.validate {
+                items.extend(validate::validate_items(types, container, !self.hide_kube)?);
+            }
+            if self.validate_cel {
+                items.extend(validate::validate_cel_items(types, container, !self.hide_kube)?);
+                evaluates_rules |= validate::evaluates_rules(types, container, !self.hide_kube);
+            }
+        }
+
+        let json_schema = self.derive_traits.iter().any(|d| d.derived_trait == "JsonSchema");
+        items.extend(formats::support_items(
+            &rendered,
+            &self.enabled_format_types(),
+            json_schema,
+        )?);
+        items.extend(validate::support_items(
+            self.validate,
+            self.validate_cel,
+            evaluates_rules,
+        )?);
+        if embeds_schema {
+            items.extend(schema::support_items(types)?);
+        }
+        if rendered.iter().any(|c| c.uses_explicit_null()) {
+            items.push(parse_item(quote! {
+                /// Deserialize a present value as `Some`, so that an explicit null becomes `Some(None)`
+                fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                    T: Deserialize<'de>,
+                {
+                    T::deserialize(deserializer).map(Some)
+                }
+            })?);
+        }
+
+        Ok(unparse(items))
+    }
+
+    /// The type definition for a container, followed by impls for its schema defaults
+    fn container_items(
+        &self,
+        types: &GeneratedTypes,
+        container: &Container,
+    ) -> anyhow::Result<Vec<syn::Item>> {
+        let structs = &types.containers;
+        let renames = types.renames();
+        let name = format_ident!("{}", types.type_name(container));
+
+        let docs = self.doc_attrs(&with_rules(&container.docs, &container.rules));
+        let derives = self.derives(container, structs)?;
+        let kube_attrs = if container.is_main_container() {
+            types.kube_attrs.as_slice()
+        } else {
+            &[]
+        };
+        let container_serde_attr = serde_attr(&container.serde_annot)?;
+
+        let mut members = vec![];
+        for member in &container.members {
+            let docs = self.doc_attrs(&with_rules(&member.docs, types.member_rules(member)));
+
+            let mut serde_annot = member.serde_annot.clone();
+            if member.default.is_some() && !container.is_enum {
+                // schema defaults are supplied by a generated fn rather than Default::default
+                serde_annot.retain(|annot| annot != "default");
+                serde_annot.insert(0, format!(r#"default = "{}::{}""#, name, member.default_fn()));
+            }
+            // variants of integer enums are serialized as their discriminants, so their names are not renamed
+            let member_serde_attr = match container.repr {
+                Some(_) => None,
+                None => serde_attr(&serde_annot)?,
+            };
+
+            let extra_attrs = member
+                .extra_annot
+                .iter()
+                .map(|annot| {
+                    syn::parse::Parser::parse_str(syn::Attribute::parse_outer, annot)
+                        .with_context(|| format!("invalid attribute `{}`", annot))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+                .into_iter()
+                .flatten();
+
+            // types without a JsonSchema impl, described by the schema of what they serialize as
+            let schema_type = replace_type_names(&member.type_, |name| {
+                let string_like = name == "ByteString"
+                    || (self.datetime == DateTimeBackend::Time && matches!(name, "OffsetDateTime" | "Date"));
+                if name == "DynamicObject" {
+                    Some("serde_json::Value".to_string())
+                } else {
+                    string_like.then(|| "String".to_string())
+                }
+            });
+            let schema_attr = (derives.iter().any(|d| d.is_ident("JsonSchema"))
+                && schema_type != member.type_)
+                .then(|| quote!(#[schemars(with = #schema_type)]));
+
+            let default_attr = (container.default_variant.as_ref() == Some(&member.name)
+                && self.implements_default(container, structs))
+            .then(|| quote!(#[default]));
+
+            let member_name = format_ident!("{}", member.name);
+            let attrs = quote! {
+                #(#docs)*
+                #member_serde_attr
+                #(#extra_attrs)*
+                #schema_attr
+                #default_attr
+            };
+
+            members.push(if container.repr.is_some() {
+                let discriminant = syn::parse_str::<syn::Expr>(member.original_name())
+                    .with_context(|| format!("invalid discriminant `{}`", member.original_name()))?;
+                quote!(#attrs #member_name = #discriminant)
+            } else if container.is_enum && member.type_.is_empty() {
+                quote!(#attrs #member_name)
+            } else if container.is_enum {
+                let ty = parse_type(&member.type_, &renames)?;
+                quote!(#attrs #member_name(#ty))
+            } else {
+                let ty = parse_type(&member.type_, &renames)?;
+                quote!(#attrs pub #member_name: #ty)
+            });
+        }
+
+        let repr_attr = match &container.repr {
+            Some(repr) => {
+                let repr = format_ident!("{}", repr);
+                Some(quote!(#[repr(#repr)]))
+            }
+            None => None,
+        };
+        let definition = if container.is_enum {
+            quote! {
+                #(#docs)*
+                #[derive(#(#derives),*)]
+                #(#[kube(#kube_attrs)])*
+                #container_serde_attr
+                #repr_attr
+                pub enum #name {
+                    #(#members,)*
+                }
+            }
+        } else {
+            quote! {
+                #(#docs)*
+                #[derive(#(#derives),*)]
+                #(#[kube(#kube_attrs)])*
+                #container_serde_attr
+                pub struct #name {
+                    #(#members,)*
+                }
+            }
+        };
+
+        let mut items = vec![parse_item(definition)?];
+
+        if container.has_defaults() {
+            let mut default_fns = vec![];
+            for member in &container.members {
+                let Some(expr) = member.default_expr(structs) else {
+                    continue;
+                };
+                let fn_name = format_ident!("{}", member.default_fn());
+                let ty = parse_type(&member.type_, &renames)?;
+                let mut expr = syn::parse_str::<syn::Expr>(&expr)
+                    .with_context(|| format!("invalid default expression `{}`", expr))?;
+                Renamer(&renames).visit_expr_mut(&mut expr);
+
+                default_fns.push(quote! {
+                    fn #fn_name() -> #ty {
+                        #expr
+                    }
+                });
+            }
+
+            items.push(parse_item(quote! {
+                impl #name {
+                    #(#default_fns)*
+                }
+            })?);
+
+            if self.implements_default(container, structs) {
+                let fields = container.members.iter().map(|member| {
+                    let member_name = format_ident!("{}", member.name);
+                    if member.default.is_some() {
+                        let fn_name = format_ident!("{}", member.default_fn());
+                        quote!(#member_name: Self::#fn_name())
+                    } else {
+                        quote!(#member_name: Default::default())
+                    }
+                });
+
+                items.push(parse_item(quote! {
+                    impl Default for #name {
+                        fn default() -> Self {
+                            Self {
+                                #(#fields,)*
+                            }
+                        }
+                    }
+                })?);
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn doc_attrs(&self, doc: &Option<String>) -> Vec<TokenStream> {
+        // print doc strings if requested in arguments
+        match doc {
+            Some(docstring) if self.emit_docs => docstr_lines(docstring)
+                .into_iter()
+                .map(|line| {
+                    let line = format!(" {}", line);
+                    quote!(#[doc = #line])
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn derives(&self, struct_def: &Container, containers: &[Container]) -> anyhow::Result<Vec<syn::Path>> {
+        let mut derives = vec!["Serialize", "Deserialize", "Clone", "Debug"];
+
+        if struct_def.is_main_container() && !self.hide_kube {
+            // CustomResource first for root struct
+            derives.insert(0, "CustomResource");
+        }
+
+        // TypedBuilder does not work with enums
+        if self.builders && !struct_def.is_enum {
+            derives.push("TypedBuilder");
+        }
+
+        for derive in &self.derive_traits {
+            if derive.derived_trait == "JsonSchema"
+                && self.schema_mode == SchemaMode::Preserved
+                && schema::embeds_schema(struct_def)
+            {
+                // implemented with the preserved schema instead
+                continue;
+            }
+
+            if derive.derived_trait == "Default"
+                && (!self.implements_default(struct_def, containers) || struct_def.has_defaults())
+            {
+                // a container with schema defaults gets a manual impl instead
+                continue;
+            }
+
+            if derive.is_applicable_to(struct_def) && !derives.contains(&derive.derived_trait.as_str()) {
+                derives.push(&derive.derived_trait)
+            }
+        }
+
+        if struct_def.repr.is_some() {
+            // integer enums are (de)serialized and described by their discriminants
+            for derive in &mut derives {
+                *derive = match *derive {
+                    "Serialize" => "Serialize_repr",
+                    "Deserialize" => "Deserialize_repr",
+                    "JsonSchema" => "JsonSchema_repr",
+                    other => other,
+                };
+            }
+        }
+
+        derives
+            .into_iter()
+            .map(|derive| syn::parse_str(derive).with_context(|| format!("invalid derive `{}`", derive)))
+            .collect()
+    }
+
+    /// Whether `Default` should be implemented (derived or manually) for a container
+    pub(crate) fn implements_default(&self, container: &Container, containers: &[Container]) -> bool {
+        if container.is_enum && container.default_variant.is_none() {
+            return false;
+        }
+
+        if self.smart_derive_elision && !container.can_derive_default(containers) {
+            return false;
+        }
+
+        self.derive_traits
+            .iter()
+            .any(|derive| derive.derived_trait == "Default" && derive.is_applicable_to(container))
+    }
+
+    fn prelude_items(&self, imports: &[String]) -> anyhow::Result<Vec<syn::Item>> {
+        let imports = imports
+            .iter()
+            .map(|import| {
+                syn::parse_str::<syn::UseTree>(import).with_context(|| format!("invalid import `{}`", import))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(vec![
+            parse_item(quote! {
+                #[allow(unused_imports)]
+                mod prelude {
+                    #(pub use #imports;)*
+                }
+            })?,
+            parse_item(quote!(
+                use self::prelude::*;
+            ))?,
+        ])
+    }
+}
+
+/// Documentation followed by a list of `x-kubernetes-validations` rules, if there are any
+fn with_rules(docs: &Option<String>, rules: &[ValidationRule]) -> Option<String> {
+    if rules.is_empty() {
+        return docs.clone();
+    }
+
+    let mut lines = vec![];
+    if let Some(docs) = docs {
+        lines.push(docs.clone());
+        lines.push(String::new());
+    }
+    lines.push("Validation rules:".to_string());
+    for rule in rules {
+        // rules and messages spanning multiple lines are joined to keep the list intact
+        let expression = rule.rule.split_whitespace().collect::<Vec<_>>().join(" ");
+        match &rule.message {
+            Some(message) => lines.push(format!(
+                "- `{}`: {}",
+                expression,
+                message.split_whitespace().collect::<Vec<_>>().join(" ")
+            )),
+            None => lines.push(format!("- `{}`", expression)),
+        }
+    }
+    Some(lines.join("\n"))
+}
+
+/// Renames references to generated types, leaving all other identifiers alone
+struct Renamer<'a>(&'a HashMap<String, String>);
+
+impl VisitMut for Renamer<'_> {
+    fn visit_path_segment_mut(&mut self, segment: &mut syn::PathSegment) {
+        if let Some(name) = self.0.get(&segment.ident.to_string()) {
+            segment.ident = syn::Ident::new(name, segment.ident.span());
+        }
+        syn::visit_mut::visit_path_segment_mut(self, segment);
+    }
+}
+
+pub(crate) fn parse_type(type_: &str, renames: &HashMap<String, String>) -> anyhow::Result<syn::Type> {
+    let mut ty = syn::parse_str::<syn::Type>(type_).with_context(|| format!("invalid type `{}`", type_))?;
+    Renamer(renames).visit_type_mut(&mut ty);
+    Ok(ty)
+}
+
+fn parse_tokens(tokens: &str) -> anyhow::Result<TokenStream> {
+    syn::parse_str(tokens).with_context(|| format!("invalid attribute arguments `{}`", tokens))
+}
+
+pub(crate) fn parse_item(tokens: TokenStream) -> anyhow::Result<syn::Item> {
+    syn::parse2(tokens).context("generated an invalid item")
+}
+
+/// The type argument of a generic type with the given prefix (e.g. `Option<`), if `type_` is one
+pub(crate) fn unwrap_generic<'a>(type_: &'a str, prefix: &str) -> Option<&'a str> {
+    type_.strip_prefix(prefix)?.strip_suffix('>')
+}
+
+/// A `#[serde(...)]` attribute for the given annotations, if any
+fn serde_attr(annotations: &[String]) -> anyhow::Result<Option<TokenStream>> {
+    if annotations.is_empty() {
+        return Ok(None);
+    }
+
+    let tokens = parse_tokens(&annotations.join(", "))?;
+    Ok(Some(quote!(#[serde(#tokens)])))
+}
+
+/// Pretty print items, separated by blank lines
+fn unparse(items: Vec<syn::Item>) -> String {
+    items
+        .into_iter()
+        .map(|item| {
+            prettyplease::unparse(&syn::File {
+                shebang: None,
+                attrs: vec![],
+                items: vec![item],
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
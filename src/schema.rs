@@ -0,0 +1,87 @@
+//! Renders `JsonSchema` impls embedding the original schema, for `SchemaMode::Preserved`
+use anyhow::Context;
+use heck::ToUpperCamelCase;
+use proc_macro2::Literal;
+use quote::{format_ident, quote};
+
+use crate::{render::parse_item, Container, GeneratedTypes};
+
+/// The `JsonSchema` impl of the main or status container, embedding its part of the original schema
+///
+/// The main container is followed by an impl for the custom resource, embedding the whole schema.
+pub(crate) fn schema_items(
+    types: &GeneratedTypes,
+    container: &Container,
+    with_resource: bool,
+) -> anyhow::Result<Vec<syn::Item>> {
+    let property = if container.is_main_container() {
+        "spec"
+    } else if container.is_status_container() {
+        "status"
+    } else {
+        return Ok(vec![]);
+    };
+
+    let mut items = vec![json_schema_impl(&types.type_name(container), &[property])?];
+    if with_resource && container.is_main_container() {
+        // kube-derive names the resource after the kind in upper camel case, like the spec
+        items.push(json_schema_impl(&types.kind.to_upper_camel_case(), &[])?);
+    }
+    Ok(items)
+}
+
+/// Whether a container has a `JsonSchema` impl embedding the original schema
+pub(crate) fn embeds_schema(container: &Container) -> bool {
+    container.is_main_container() || container.is_status_container()
+}
+
+/// The original schema and the fn reading it, emitted once per rendered module that embeds it
+pub(crate) fn support_items(types: &GeneratedTypes) -> anyhow::Result<Vec<syn::Item>> {
+    let schema = match &types.original_schema {
+        Some(schema) => schema.clone(),
+        None => serde_json::to_string(&types.schema).context("failed to serialize schema")?,
+    };
+    // a raw string keeps the embedded json readable, with enough hashes to not end early
+    let mut hashes = String::from("#");
+    while schema.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    let schema = format!("r{hashes}\"{schema}\"{hashes}")
+        .parse::<Literal>()
+        .map_err(|error| anyhow::anyhow!("failed to embed schema: {}", error))?;
+    [
+        quote! {
+            /// The `openAPIV3Schema` of the CRD version, as found upstream
+            const OPEN_API_V3_SCHEMA: &str = #schema;
+        },
+        quote! {
+            /// The original schema, or the schema of the property at `path` in it
+            fn preserved_schema(path: &[&str]) -> schemars::Schema {
+                let mut schema: serde_json::Value =
+                    serde_json::from_str(OPEN_API_V3_SCHEMA).expect("valid embedded schema");
+                for property in path {
+                    schema = schema["properties"][property].take();
+                }
+                serde_json::from_value(schema).expect("valid embedded schema")
+            }
+        },
+    ]
+    .into_iter()
+    .map(parse_item)
+    .collect()
+}
+
+fn json_schema_impl(name: &str, path: &[&str]) -> anyhow::Result<syn::Item> {
+    let ident = format_ident!("{}", name);
+    parse_item(quote! {
+        impl schemars::JsonSchema for #ident {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                #name.into()
+            }
+
+            fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                preserved_schema(&[#(#path),*])
+            }
+        }
+    })
+}
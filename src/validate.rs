@@ -0,0 +1,766 @@
+//! Renders `Validate` impls checking the value constraints of the schema, and `ValidateCel` impls
+//! evaluating its `x-kubernetes-validations` rules
+use heck::ToUpperCamelCase;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::ValidationRule;
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::{
+    formats,
+    render::{parse_item, unwrap_generic},
+    Constraints, Container, GeneratedTypes, Member,
+};
+
+/// The validation traits and error types, emitted once per rendered module
+///
+/// The `CelRule` evaluating validation rules is only emitted when a rendered type `evaluates_rules`.
+pub(crate) fn support_items(
+    validate: bool,
+    validate_cel: bool,
+    evaluates_rules: bool,
+) -> anyhow::Result<Vec<syn::Item>> {
+    if !validate && !validate_cel {
+        return Ok(vec![]);
+    }
+
+    let mut items = vec![
+        quote! {
+            /// A value violating a constraint or validation rule of its schema
+            #[derive(Clone, Debug, PartialEq)]
+            pub struct ValidationError {
+                /// JSON path of the value (e.g. `spec.endpoints[2].port`)
+                pub path: String,
+                /// Description of the violated constraint
+                pub message: String,
+            }
+        },
+        quote! {
+            /// All violations found by validating a value
+            #[derive(Clone, Debug, PartialEq)]
+            pub struct ValidationErrors(pub Vec<ValidationError>);
+        },
+        quote! {
+            impl std::fmt::Display for ValidationErrors {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    let errors = self
+                        .0
+                        .iter()
+                        .map(|error| format!("{}: {}", error.path, error.message))
+                        .collect::<Vec<_>>();
+                    write!(f, "{}", errors.join(", "))
+                }
+            }
+        },
+        quote! {
+            impl std::error::Error for ValidationErrors {}
+        },
+    ];
+
+    if validate {
+        items.extend([
+            quote! {
+                /// Checks values against the constraints of their schema
+                ///
+                /// Required members are not checked, as they cannot be missing from a deserialized value.
+                pub trait Validate {
+                    /// Push every constraint violation of this value, found at `path`, to `errors`
+                    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>);
+
+                    /// Check this value against the constraints of its schema
+                    fn validate(&self) -> Result<(), ValidationErrors> {
+                        let mut errors = vec![];
+                        self.validate_at("", &mut errors);
+                        if errors.is_empty() {
+                            Ok(())
+                        } else {
+                            Err(ValidationErrors(errors))
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<T: Validate> Validate for Vec<T> {
+                    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+                        for (i, item) in self.iter().enumerate() {
+                            item.validate_at(&format!("{}[{}]", path, i), errors);
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<T: Validate> Validate for std::collections::BTreeMap<String, T> {
+                    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+                        for (key, value) in self {
+                            value.validate_at(&validation_path(path, key), errors);
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<T: Validate> Validate for std::collections::HashMap<String, T> {
+                    fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+                        for (key, value) in self {
+                            value.validate_at(&validation_path(path, key), errors);
+                        }
+                    }
+                }
+            },
+        ]);
+    }
+
+    if validate_cel {
+        items.extend([
+            quote! {
+                /// Evaluates the `x-kubernetes-validations` rules of the schema against values
+                pub trait ValidateCel {
+                    /// Push every violated rule of this value, found at `path`, to `errors`
+                    ///
+                    /// The `old` value is bound to `oldSelf` in transition rules, which are skipped without it.
+                    fn validate_cel_at(&self, old: Option<&Self>, path: &str, errors: &mut Vec<ValidationError>);
+
+                    /// Evaluate the validation rules of the schema against this value, and an `old` value on updates
+                    fn validate_cel(&self, old: Option<&Self>) -> Result<(), ValidationErrors> {
+                        let mut errors = vec![];
+                        self.validate_cel_at(old, "", &mut errors);
+                        if errors.is_empty() {
+                            Ok(())
+                        } else {
+                            Err(ValidationErrors(errors))
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<T: ValidateCel> ValidateCel for Vec<T> {
+                    fn validate_cel_at(&self, _old: Option<&Self>, path: &str, errors: &mut Vec<ValidationError>) {
+                        // list items are not correlated with old items
+                        for (i, item) in self.iter().enumerate() {
+                            item.validate_cel_at(None, &format!("{}[{}]", path, i), errors);
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<T: ValidateCel> ValidateCel for std::collections::BTreeMap<String, T> {
+                    fn validate_cel_at(&self, old: Option<&Self>, path: &str, errors: &mut Vec<ValidationError>) {
+                        for (key, value) in self {
+                            let old = old.and_then(|old| old.get(key));
+                            value.validate_cel_at(old, &validation_path(path, key), errors);
+                        }
+                    }
+                }
+            },
+            quote! {
+                impl<T: ValidateCel> ValidateCel for std::collections::HashMap<String, T> {
+                    fn validate_cel_at(&self, old: Option<&Self>, path: &str, errors: &mut Vec<ValidationError>) {
+                        for (key, value) in self {
+                            let old = old.and_then(|old| old.get(key));
+                            value.validate_cel_at(old, &validation_path(path, key), errors);
+                        }
+                    }
+                }
+            },
+        ]);
+    }
+
+    if validate_cel && evaluates_rules {
+        items.extend([
+            quote! {
+                /// An `x-kubernetes-validations` rule
+                struct CelRule {
+                    rule: &'static str,
+                    message: Option<&'static str>,
+                    message_expression: Option<&'static str>,
+                    field_path: Option<&'static str>,
+                }
+            },
+            quote! {
+                impl CelRule {
+                    fn evaluate<T: serde::Serialize>(
+                        &self,
+                        value: &T,
+                        old: Option<&T>,
+                        path: &str,
+                        errors: &mut Vec<ValidationError>,
+                    ) {
+                        if old.is_none() && self.rule.contains("oldSelf") {
+                            return;
+                        }
+
+                        let path = match self.field_path {
+                            Some(field_path) => validation_path(path, field_path.trim_start_matches('.')),
+                            None => path.to_string(),
+                        };
+                        let message = Self::context(value, old).and_then(|context| {
+                            if Self::execute(self.rule, &context)? == cel::Value::Bool(true) {
+                                return Ok(None);
+                            }
+                            // like the api server, fall back to the message if the message expression fails
+                            let message = self
+                                .message_expression
+                                .and_then(|expression| match Self::execute(expression, &context) {
+                                    Ok(cel::Value::String(message)) => Some(message.to_string()),
+                                    _ => None,
+                                })
+                                .or_else(|| self.message.map(String::from))
+                                .unwrap_or_else(|| format!("failed rule: {}", self.rule));
+                            Ok(Some(message))
+                        });
+
+                        match message {
+                            Ok(None) => {}
+                            Ok(Some(message)) => errors.push(ValidationError { path, message }),
+                            Err(error) => errors.push(ValidationError {
+                                path,
+                                message: format!("failed to evaluate rule `{}`: {}", self.rule, error),
+                            }),
+                        }
+                    }
+
+                    fn context<T: serde::Serialize>(value: &T, old: Option<&T>) -> Result<cel::Context<'static>, String> {
+                        let mut context = cel::Context::default();
+                        let value = cel::to_value(value).map_err(|error| error.to_string())?;
+                        context.add_variable_from_value("self", value);
+                        if let Some(old) = old {
+                            let old = cel::to_value(old).map_err(|error| error.to_string())?;
+                            context.add_variable_from_value("oldSelf", old);
+                        }
+                        Ok(context)
+                    }
+
+                    fn execute(expression: &str, context: &cel::Context) -> Result<cel::Value, String> {
+                        let program = cel::Program::compile(expression).map_err(|error| error.to_string())?;
+                        program.execute(context).map_err(|error| error.to_string())
+                    }
+                }
+            },
+        ]);
+    }
+
+    items.push(quote! {
+        fn validation_path(path: &str, name: &str) -> String {
+            if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", path, name)
+            }
+        }
+    });
+
+    items.into_iter().map(parse_item).collect()
+}
+
+/// The `Validate` impl of a container
+///
+/// The main container is followed by an impl for the custom resource, validating its spec and status.
+pub(crate) fn validate_items(
+    types: &GeneratedTypes,
+    container: &Container,
+    with_resource: bool,
+) -> anyhow::Result<Vec<syn::Item>> {
+    let name = format_ident!("{}", types.type_name(container));
+
+    let body = if container.is_enum {
+        let mut arms = vec![];
+        for member in &container.members {
+            let path = if container.serde_annot.is_empty() {
+                // externally tagged, so the value is found under the variant name
+                let original_name = member.original_name();
+                quote!(validation_path(path, #original_name))
+            } else {
+                quote!(path.to_string())
+            };
+            let Some(checks) = member_checks(types, member, &member.type_, path) else {
+                continue;
+            };
+            let variant = format_ident!("{}", member.name);
+            arms.push(quote!(Self::#variant(value) => { #checks }));
+        }
+        if arms.is_empty() {
+            vec![]
+        } else {
+            let rest = (arms.len() < container.members.len()).then(|| quote!(_ => {}));
+            vec![quote! {
+                match self {
+                    #(#arms)*
+                    #rest
+                }
+            }]
+        }
+    } else {
+        let mut blocks = vec![];
+        for member in &container.members {
+            let original_name = member.original_name();
+            let path = quote!(validation_path(path, #original_name));
+            let member_name = format_ident!("{}", member.name);
+            if let Some((inner, present, _)) = optional_value(&member.type_) {
+                if let Some(checks) = member_checks(types, member, inner, path) {
+                    blocks.push(quote! {
+                        if let #present = &self.#member_name {
+                            #checks
+                        }
+                    });
+                }
+            } else if let Some(checks) = member_checks(types, member, &member.type_, path) {
+                blocks.push(quote! {
+                    {
+                        let value = &self.#member_name;
+                        #checks
+                    }
+                });
+            }
+        }
+        blocks
+    };
+
+    let (path, errors) = if body.is_empty() {
+        (format_ident!("_path"), format_ident!("_errors"))
+    } else {
+        (format_ident!("path"), format_ident!("errors"))
+    };
+    let mut items = vec![parse_item(quote! {
+        impl Validate for #name {
+            fn validate_at(&self, #path: &str, #errors: &mut Vec<ValidationError>) {
+                #(#body)*
+            }
+        }
+    })?];
+
+    if with_resource && container.is_main_container() {
+        // kube-derive names the resource after the kind in upper camel case, like the spec
+        let kind = format_ident!("{}", types.kind.to_upper_camel_case());
+        let status = has_status(types).then(|| {
+            quote! {
+                if let Some(status) = &self.status {
+                    status.validate_at(&validation_path(path, "status"), errors);
+                }
+            }
+        });
+        items.push(parse_item(quote! {
+            impl Validate for #kind {
+                fn validate_at(&self, path: &str, errors: &mut Vec<ValidationError>) {
+                    self.spec.validate_at(&validation_path(path, "spec"), errors);
+                    #status
+                }
+            }
+        })?);
+    }
+
+    Ok(items)
+}
+
+/// The `ValidateCel` impl of a container
+///
+/// The main container is followed by an impl for the custom resource, which also evaluates the rules of the root schema.
+pub(crate) fn validate_cel_items(
+    types: &GeneratedTypes,
+    container: &Container,
+    with_resource: bool,
+) -> anyhow::Result<Vec<syn::Item>> {
+    let name = format_ident!("{}", types.type_name(container));
+
+    let mut body = container
+        .rules
+        .iter()
+        .map(|rule| {
+            let rule = cel_rule(rule);
+            quote!(#rule.evaluate(self, old, path, errors);)
+        })
+        .collect::<Vec<_>>();
+
+    if container.is_enum {
+        let mut arms = vec![];
+        for member in &container.members {
+            let path = if container.serde_annot.is_empty() {
+                // externally tagged, so the value is found under the variant name
+                let original_name = member.original_name();
+                quote!(validation_path(path, #original_name))
+            } else {
+                quote!(path.to_string())
+            };
+            let Some(checks) = member_rule_checks(types, member, &member.type_, path) else {
+                continue;
+            };
+            let variant = format_ident!("{}", member.name);
+            arms.push(quote! {
+                Self::#variant(value) => {
+                    let old = match old {
+                        Some(Self::#variant(old)) => Some(old),
+                        _ => None,
+                    };
+                    #checks
+                }
+            });
+        }
+        if !arms.is_empty() {
+            let rest = (arms.len() < container.members.len()).then(|| quote!(_ => {}));
+            body.push(quote! {
+                match self {
+                    #(#arms)*
+                    #rest
+                }
+            });
+        }
+    } else {
+        for member in &container.members {
+            let original_name = member.original_name();
+            let path = quote!(validation_path(path, #original_name));
+            let member_name = format_ident!("{}", member.name);
+            if let Some((inner, present, as_ref)) = optional_value(&member.type_) {
+                if let Some(checks) = member_rule_checks(types, member, inner, path) {
+                    body.push(quote! {
+                        if let #present = &self.#member_name {
+                            let old = old.and_then(|old| old.#member_name #as_ref);
+                            #checks
+                        }
+                    });
+                }
+            } else if let Some(checks) = member_rule_checks(types, member, &member.type_, path) {
+                body.push(quote! {
+                    {
+                        let value = &self.#member_name;
+                        let old = old.map(|old| &old.#member_name);
+                        #checks
+                    }
+                });
+            }
+        }
+    }
+
+    let (old, path, errors) = if body.is_empty() {
+        (
+            format_ident!("_old"),
+            format_ident!("_path"),
+            format_ident!("_errors"),
+        )
+    } else {
+        (
+            format_ident!("old"),
+            format_ident!("path"),
+            format_ident!("errors"),
+        )
+    };
+    let mut items = vec![parse_item(quote! {
+        impl ValidateCel for #name {
+            fn validate_cel_at(&self, #old: Option<&Self>, #path: &str, #errors: &mut Vec<ValidationError>) {
+                #(#body)*
+            }
+        }
+    })?];
+
+    if with_resource && container.is_main_container() {
+        let kind = format_ident!("{}", types.kind.to_upper_camel_case());
+        let root_rules = types
+            .containers
+            .iter()
+            .filter(|container| container.is_root())
+            .flat_map(|container| &container.rules)
+            .map(|rule| {
+                let rule = cel_rule(rule);
+                quote!(#rule.evaluate(self, old, path, errors);)
+            });
+        let status = has_status(types).then(|| {
+            quote! {
+                if let Some(status) = &self.status {
+                    let old = old.and_then(|old| old.status.as_ref());
+                    status.validate_cel_at(old, &validation_path(path, "status"), errors);
+                }
+            }
+        });
+        items.push(parse_item(quote! {
+            impl ValidateCel for #kind {
+                fn validate_cel_at(&self, old: Option<&Self>, path: &str, errors: &mut Vec<ValidationError>) {
+                    #(#root_rules)*
+                    self.spec.validate_cel_at(old.map(|old| &old.spec), &validation_path(path, "spec"), errors);
+                    #status
+                }
+            }
+        })?);
+    }
+
+    Ok(items)
+}
+
+/// Statements evaluating the rules of a member `value` of type `type_`, if it has any rules
+///
+/// Values referencing other generated types are validated recursively.
+fn member_rule_checks(
+    types: &GeneratedTypes,
+    member: &Member,
+    type_: &str,
+    path: TokenStream,
+) -> Option<TokenStream> {
+    let mut checks = types
+        .member_rules(member)
+        .iter()
+        .map(|rule| {
+            let rule = cel_rule(rule);
+            quote!(#rule.evaluate(value, old, &path, errors);)
+        })
+        .collect::<Vec<_>>();
+    if references_generated_type(types, type_) {
+        checks.push(quote!(value.validate_cel_at(old, &path, errors);));
+    }
+
+    if checks.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        let path = #path;
+        #(#checks)*
+    })
+}
+
+/// A `CelRule` literal for a validation rule
+fn cel_rule(rule: &ValidationRule) -> TokenStream {
+    let optional = |value: &Option<String>| match value {
+        Some(value) => quote!(Some(#value)),
+        None => quote!(None),
+    };
+    let expression = &rule.rule;
+    let message = optional(&rule.message);
+    let message_expression = optional(&rule.message_expression);
+    let field_path = optional(&rule.field_path);
+    quote! {
+        CelRule {
+            rule: #expression,
+            message: #message,
+            message_expression: #message_expression,
+            field_path: #field_path,
+        }
+    }
+}
+
+/// Whether the `ValidateCel` impls of a container evaluate any validation rules
+pub(crate) fn evaluates_rules(types: &GeneratedTypes, container: &Container, with_resource: bool) -> bool {
+    let root_rules = with_resource
+        && container.is_main_container()
+        && types
+            .containers
+            .iter()
+            .any(|container| container.is_root() && !container.rules.is_empty());
+    root_rules
+        || !container.rules.is_empty()
+        || container
+            .members
+            .iter()
+            .any(|member| !types.member_rules(member).is_empty())
+}
+
+/// Whether the status container is rendered, and so implements the validation traits
+fn has_status(types: &GeneratedTypes) -> bool {
+    types
+        .types()
+        .any(|container| container.is_status_container() && !container.members.is_empty())
+}
+
+/// Statements checking the constraints of a member `value` of type `type_`, if there is anything to check
+///
+/// Values referencing other generated types are validated recursively.
+fn member_checks(
+    types: &GeneratedTypes,
+    member: &Member,
+    type_: &str,
+    path: TokenStream,
+) -> Option<TokenStream> {
+    let mut checks = constraint_checks(&member.constraints, type_);
+    if references_generated_type(types, type_) {
+        checks.push(quote!(value.validate_at(&path, errors);));
+    }
+
+    if checks.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        let path = #path;
+        #(#checks)*
+    })
+}
+
+fn constraint_checks(constraints: &Constraints, type_: &str) -> Vec<TokenStream> {
+    let mut checks = vec![];
+
+    let is_integer = matches!(
+        type_,
+        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128"
+    );
+    if is_integer || type_ == "f32" || type_ == "f64" {
+        let number = if type_ == "f64" {
+            quote!(*value)
+        } else {
+            quote!((*value as f64))
+        };
+        if let Some(minimum) = constraints.minimum {
+            let literal = Literal::f64_suffixed(minimum);
+            checks.push(if constraints.exclusive_minimum {
+                check(
+                    quote!(#number <= #literal),
+                    format!("must be greater than {}", minimum),
+                )
+            } else {
+                check(
+                    quote!(#number < #literal),
+                    format!("must be greater than or equal to {}", minimum),
+                )
+            });
+        }
+        if let Some(maximum) = constraints.maximum {
+            let literal = Literal::f64_suffixed(maximum);
+            checks.push(if constraints.exclusive_maximum {
+                check(
+                    quote!(#number >= #literal),
+                    format!("must be less than {}", maximum),
+                )
+            } else {
+                check(
+                    quote!(#number > #literal),
+                    format!("must be less than or equal to {}", maximum),
+                )
+            });
+        }
+        if let Some(multiple_of) = constraints.multiple_of.filter(|m| *m != 0.0) {
+            let condition = if is_integer && multiple_of.fract() == 0.0 {
+                let literal = Literal::i128_unsuffixed(multiple_of as i128);
+                quote!(*value % #literal != 0)
+            } else {
+                let literal = Literal::f64_suffixed(multiple_of);
+                quote!(#number % #literal != 0.0)
+            };
+            checks.push(check(condition, format!("must be a multiple of {}", multiple_of)));
+        }
+    }
+
+    // the newtypes generated for string formats are checked through their string
+    let string = match type_ {
+        "String" => Some((quote!(value), quote!(value))),
+        newtype if formats::is_newtype(newtype) => Some((quote!(value.0), quote!(&value.0))),
+        _ => None,
+    };
+    if let Some((string, borrowed)) = string {
+        match constraints.min_length {
+            Some(1) => checks.push(check(quote!(#string.is_empty()), "must not be empty".to_string())),
+            Some(min_length) if min_length > 1 => {
+                let literal = Literal::i64_unsuffixed(min_length);
+                checks.push(check(
+                    quote!(#string.chars().count() < #literal),
+                    format!("must be at least {} characters long", min_length),
+                ));
+            }
+            _ => {}
+        }
+        if let Some(max_length) = constraints.max_length {
+            let literal = Literal::i64_unsuffixed(max_length);
+            checks.push(check(
+                quote!(#string.chars().count() > #literal),
+                format!("must be at most {} characters long", max_length),
+            ));
+        }
+        if let Some(pattern) = &constraints.pattern {
+            let check = check(
+                quote!(!pattern.is_match(#borrowed)),
+                format!("must match the pattern `{}`", pattern),
+            );
+            checks.push(quote! {
+                {
+                    static PATTERN: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+                    let pattern = PATTERN.get_or_init(|| regex::Regex::new(#pattern).expect("valid pattern"));
+                    #check
+                }
+            });
+        }
+    }
+
+    if type_.starts_with("Vec<") || type_.starts_with("BTreeSet<") {
+        checks.extend(length_checks(
+            constraints.min_items,
+            constraints.max_items,
+            "items",
+        ));
+        // items of a set are unique by construction
+        if constraints.unique_items && type_.starts_with("Vec<") {
+            let check = check(
+                quote!((1..items.len()).any(|i| items[..i].contains(&items[i]))),
+                "must not contain duplicate items".to_string(),
+            );
+            checks.push(quote! {
+                {
+                    let items = value
+                        .iter()
+                        .map(|item| serde_json::to_value(item).ok())
+                        .collect::<Vec<_>>();
+                    #check
+                }
+            });
+        }
+    }
+
+    if type_.starts_with("BTreeMap<") || type_.starts_with("HashMap<") {
+        checks.extend(length_checks(
+            constraints.min_properties,
+            constraints.max_properties,
+            "properties",
+        ));
+    }
+
+    checks
+}
+
+/// Checks of the number of items or properties of a collection
+fn length_checks(min: Option<i64>, max: Option<i64>, unit: &str) -> Vec<TokenStream> {
+    let mut checks = vec![];
+    match min {
+        Some(1) => checks.push(check(quote!(value.is_empty()), "must not be empty".to_string())),
+        Some(min) if min > 1 => {
+            let literal = Literal::i64_unsuffixed(min);
+            checks.push(check(
+                quote!(value.len() < #literal),
+                format!("must have at least {} {}", min, unit),
+            ));
+        }
+        _ => {}
+    }
+    if let Some(max) = max {
+        let literal = Literal::i64_unsuffixed(max);
+        checks.push(check(
+            quote!(value.len() > #literal),
+            format!("must have at most {} {}", max, unit),
+        ));
+    }
+    checks
+}
+
+/// Report a `ValidationError` at `path` if `condition` holds
+fn check(condition: TokenStream, message: String) -> TokenStream {
+    quote! {
+        if #condition {
+            errors.push(ValidationError {
+                path: path.clone(),
+                message: #message.to_string(),
+            });
+        }
+    }
+}
+
+/// Whether a (possibly nested) collection of values holds a rendered container, which implements `Validate`
+fn references_generated_type(types: &GeneratedTypes, type_: &str) -> bool {
+    let mut inner = type_;
+    while let Some(item) = unwrap_generic(inner, "Vec<")
+        .or_else(|| unwrap_generic(inner, "BTreeMap<String, "))
+        .or_else(|| unwrap_generic(inner, "HashMap<String, "))
+    {
+        inner = item;
+    }
+    types.types().any(|container| container.name == inner)
+}
+
+/// The type of the value of an optional member, the pattern binding it as `value` when present, and the method
+/// chain borrowing it
+///
+/// Members telling an explicit null apart from an absent value are `Option<Option<T>>`, checked when `Some(Some(_))`.
+fn optional_value(type_: &str) -> Option<(&str, TokenStream, TokenStream)> {
+    let inner = unwrap_generic(type_, "Option<")?;
+    Some(match unwrap_generic(inner, "Option<") {
+        Some(value) => (value, quote!(Some(Some(value))), quote!(.as_ref()?.as_ref())),
+        None => (inner, quote!(Some(value)), quote!(.as_ref())),
+    })
+}